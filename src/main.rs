@@ -19,8 +19,49 @@
 extern crate stpsyr;
 use stpsyr::*;
 
+use std::env;
+use std::fs;
+use std::process;
+
 fn main() {
-    let mut s = Stpsyr::new("data/standard.csv");
+    // stpsyr validate <variant file>
+    let args: Vec<String> = env::args().collect();
+    if args.len() == 3 && args[1] == "validate" {
+        let variant = Variant::load(&args[2]).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            process::exit(2);
+        });
+        let problems = variant.validate();
+        for problem in &problems { println!("{}", problem); }
+        process::exit(if problems.is_empty() { 0 } else { 1 });
+    }
+
+    // stpsyr play <variant file> <order document>
+    // (starts a new game and plays every turn in the document)
+    if args.len() == 4 && args[1] == "play" {
+        let fail = |e: String| -> ! {
+            eprintln!("{}", e);
+            process::exit(2);
+        };
+        let variant = Variant::load(&args[2]).unwrap_or_else(|e| fail(e.to_string()));
+        let text = fs::read_to_string(&args[3]).unwrap_or_else(|e| fail(e.to_string()));
+        let mut s = Stpsyr::new(variant);
+        let diagnostics = s.read_document(&text)
+            .and_then(|document| s.play_document(&document))
+            .unwrap_or_else(|e| fail(e.to_string()));
+        for (power, diagnostics) in &diagnostics {
+            for d in diagnostics {
+                if let Some(ref error) = d.error {
+                    println!("{:?}: \"{}\": {}", power, d.line.trim(), error);
+                }
+            }
+        }
+        for result in s.describe_results() { println!("{}", result); }
+        println!("now {:?} {}", s.phase, s.year);
+        return;
+    }
+
+    let mut s = Stpsyr::new(Variant::load("data/standard.variant").unwrap());
     s.parse(&Power::from("Italy"), "A ven-tyr".to_string());
     s.apply();
     s.render_svg("meems.svg".to_string()).unwrap();
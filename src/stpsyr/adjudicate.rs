@@ -18,18 +18,15 @@ impl Stpsyr {
                     let from_idx = self.map.iter()
                         .position(|r| r.province == order.province).unwrap();
                     let to_idx = self.map.iter()
-                        .position(|r| r.province == *to).unwrap();
+                        .position(|r| r.province == to.province).unwrap();
 
                     if let Some(ref unit) = self.map[to_idx].unit {
-                        dislodged.push((to.clone(), unit.clone()));
+                        dislodged.push((to.province.clone(), unit.clone()));
                     }
 
-                    self.map[to_idx].unit = old_map[from_idx].unit.clone();
-
-                    if to.coast.is_some() {
-                        self.map[to_idx].province.coast =
-                            self.map[to_idx].province.coast.and(to.coast);
-                    }
+                    // fleets end up on the coast they moved to
+                    self.map[to_idx].unit = old_map[from_idx].unit.clone()
+                        .map(|unit| Unit { coast: to.coast, ..unit });
 
                     moved_away.push(&order.province);
                 } else {
@@ -43,8 +40,9 @@ impl Stpsyr {
             let p_dislodged = dislodged.iter().find(|d| d.0 == region.province);
             let p_moved_away = moved_away.contains(&&region.province);
             if let Some(dislodgement) = p_dislodged {
-                if !p_moved_away {
+                if !p_moved_away && !dislodgement.1.owner.is_neutral() {
                     // dislodged and not moved away: add it to the list
+                    // (neutrals don't retreat, they were just removed)
                     self.dislodged.push(dislodgement.clone());
                 }
             } else if p_moved_away {
@@ -137,13 +135,15 @@ impl Stpsyr {
                 true
             },
 
-            Action::Move { to, convoyed } => {
+            Action::Move { to: Location { province: to, .. }, convoyed } => {
                 let attack_strength = self.attack_strength(&province);
 
                 // the attack strength (above) needs to be greater than this
                 let counter_strength = if !convoyed && self.orders.iter().any(|o|
                         match o.action {
-                            Action::Move { to: ref move_to, convoyed } =>
+                            Action::Move {
+                                to: Location { province: ref move_to, .. }, convoyed
+                            } =>
                                 province == *move_to && !convoyed,
                             _ => false
                         } && o.province == to) {
@@ -158,7 +158,7 @@ impl Stpsyr {
                 //   units moving to the same space
                 let contesting_orders = self.orders.iter().filter(|o|
                     match o.action {
-                        Action::Move { to: ref move_to, .. } =>
+                        Action::Move { to: Location { province: ref move_to, .. }, .. } =>
                             to == *move_to,
                         _ => false
                     } && o.province != province).map(|o| o.province.clone())
@@ -169,11 +169,13 @@ impl Stpsyr {
                     .all(|p| attack_strength > self.prevent_strength(p))
             },
 
-            Action::SupportHold { to } | Action::SupportMove { to, .. } => {
+            Action::SupportHold { to, .. } | Action::SupportMove { to, .. } => {
                 // a support is cut when...
                 !self.orders.clone().iter().any(|o|
                     match o.action {
-                        Action::Move { to: ref move_to, convoyed } =>
+                        Action::Move {
+                            to: Location { province: ref move_to, .. }, convoyed
+                        } =>
                             // ... something with a valid path attacks it...
                             province == *move_to && if convoyed {
                                 !self.convoy_paths(o).is_empty()
@@ -190,7 +192,9 @@ impl Stpsyr {
                 // a convoy only fails when it is dislodged
                 !self.orders.clone().iter().any(|o|
                     match o.action {
-                        Action::Move { to: ref move_to, .. } => {
+                        Action::Move {
+                            to: Location { province: ref move_to, .. }, ..
+                        } => {
                             province == *move_to
                         },
                         _ => false
@@ -204,7 +208,9 @@ impl Stpsyr {
     //   its destination, taking into account dislodged fleets
     fn convoy_paths(&mut self, order: &Order) -> Vec<Vec<Province>> {
         match order.action {
-            Action::Move { ref to, convoyed } => { if convoyed {
+            Action::Move {
+                to: Location { province: ref to, .. }, convoyed
+            } => { if convoyed {
 
                 // first, find all paths at all through water that get from
                 //   the province of the order to the destination
@@ -240,13 +246,13 @@ impl Stpsyr {
         // the "end" of the current chain
         let region = path.last().unwrap().clone();
         // if we've made it already, return
-        if region.fleet_borders.contains(target) { return vec![path]; }
+        let borders = |r: &MapRegion, p: &Province|
+            r.fleet_borders.iter().any(|b| b.to.province == *p);
+        if borders(region, target) { return vec![path]; }
         // otherwise, find the next fleet in the chain
         self.map.iter().filter(|&r|
-                // it's empty water if we can move to it as a fleet but can't
-                // move to it as an army
-                region.fleet_borders.contains(&r.province) &&
-                !region.army_borders.contains(&r.province) &&
+                // only fleets in convoy-capable provinces can convoy
+                borders(region, &r.province) && r.convoy &&
                 // check for the presence of the appropriate order
                 self.orders.iter().any(|o|
                     o.province == r.province && match o.action {
@@ -283,7 +289,7 @@ impl Stpsyr {
                 //   support hold
                 1 + self.orders.clone().iter().filter(|o|
                     match o.action {
-                        Action::SupportHold { ref to } => *to == *province,
+                        Action::SupportHold { ref to, .. } => *to == *province,
                         _ => false
                     } && self.resolve(o.id)).count()
             }
@@ -302,7 +308,9 @@ impl Stpsyr {
                 } && o.province == *province) { move_order }
             else { panic!("attack_strength called on non-Move"); }.clone();
         let (dest, convoyed) = match move_order.action {
-            Action::Move { ref to, convoyed } => (to, convoyed),
+            Action::Move {
+                to: Location { province: ref to, .. }, convoyed
+            } => (to, convoyed),
             _ => unreachable!()
         };
 
@@ -315,7 +323,9 @@ impl Stpsyr {
         //   the recursion to become infinite
         let move_id = self.orders.iter().find(|o|
             (convoyed || match o.action {
-                Action::Move { ref to, convoyed } => convoyed || *to != *province,
+                Action::Move {
+                    to: Location { province: ref to, .. }, convoyed
+                } => convoyed || *to != *province,
                 _ => false
             }) && o.province == *dest).map(|o| o.id);
         let moved_away = move_id.map_or(false, |id| self.resolve(id));
@@ -335,7 +345,7 @@ impl Stpsyr {
         //   to support the move
         let supports: Vec<usize> = self.orders.iter().filter(|o|
             match o.action {
-                Action::SupportMove { ref from, ref to } =>
+                Action::SupportMove { ref from, ref to, .. } =>
                     *from == *province && *to == *dest,
                 _ => false
             } &&
@@ -353,14 +363,14 @@ impl Stpsyr {
                 } && o.province == *province) { move_order }
             else { panic!("defend_strength called on non-Move"); }.clone();
         let dest = match move_order.action {
-            Action::Move { ref to, .. } => to,
+            Action::Move { to: Location { province: ref to, .. }, .. } => to,
             _ => unreachable!()
         };
 
         // defend strength is just 1 plus number of successful support moves
         let supports: Vec<usize> = self.orders.iter().filter(|o|
             match o.action {
-                Action::SupportMove { ref from, ref to } =>
+                Action::SupportMove { ref from, ref to, .. } =>
                     *from == *province && *to == *dest,
                 _ => false
             }).map(|o| o.id).collect();
@@ -376,7 +386,9 @@ impl Stpsyr {
                 } && o.province == *province) { move_order }
             else { panic!("prevent_strength called on non-Move"); }.clone();
         let (dest, convoyed) = match move_order.action {
-            Action::Move { ref to, convoyed } => (to, convoyed),
+            Action::Move {
+                to: Location { province: ref to, .. }, convoyed
+            } => (to, convoyed),
             _ => unreachable!()
         };
 
@@ -386,7 +398,9 @@ impl Stpsyr {
         // if we're in a head-to-head battle and lose, prevent strength is 0
         let move_id = self.orders.iter().find(|o|
             match o.action {
-                Action::Move { ref to, convoyed } => !convoyed && *to == *province,
+                Action::Move {
+                    to: Location { province: ref to, .. }, convoyed
+                } => !convoyed && *to == *province,
                 _ => false
             } && o.province == *dest).map(|o| o.id);
         if let Some(move_id) = move_id {
@@ -396,7 +410,7 @@ impl Stpsyr {
         // otherwise, 1 plus number of successful support moves
         let supports: Vec<usize> = self.orders.iter().filter(|o|
             match o.action {
-                Action::SupportMove { ref from, ref to } =>
+                Action::SupportMove { ref from, ref to, .. } =>
                     *from == *province && *to == *dest,
                 _ => false
             }).map(|o| o.id).collect();
@@ -1,34 +1,40 @@
+use std::collections::HashSet;
+
 use stpsyr::types::*;
 
 impl Stpsyr {
 
-    // the publicly exposed function to modify self.adjusts
-    pub fn add_adjust(&mut self, owner: Power, province: Province, action: AdjustAction) {
+    // the publicly exposed function to modify self.adjusts, returning whether the
+    //   adjust was accepted (in which case it replaces any adjust already
+    //   given for the province)
+    pub fn add_adjust(&mut self, owner: Power, province: Province, action: AdjustAction)
+            -> bool {
         match self.phase {
             Phase::Builds => {},
             _ => panic!("add_adjust called during non-build phase")
         }
 
+        // eliminated powers (and powers in civil disorder) can't give orders
+        let owner = if let Some(owner) = self.orderer(&owner) { owner }
+            else { return false; };
+
         // find difference in SC and unit counts
         let delta =
             if let Some(count) = self.sc_counts().get(&owner) { *count as i32 }
-                else { return; } -
+                else { return false; } -
             if let Some(count) = self.unit_counts().get(&owner) { *count as i32 }
-                else { return; };
+                else { return false; };
 
-        // find existing number of adjust orders for this power
-        let mut dup = false;
-        let num = self.adjusts.iter()
-            .filter(|&&Adjust { owner: ref o, province: ref p, .. }|
-                    if owner == *o && province == *p {
-                        dup = true; true
-                    } else { owner == *o }).count() as i32;
+        // find existing number of adjust orders for this power (other than one
+        //   this would replace)
+        let num = self.adjusts.iter().filter(|a|
+            a.owner == owner && a.province != province).count() as i32;
 
         // fail if we're not allowed to build or destroy at all
-        if dup || match action {
+        if match action {
             AdjustAction::Disband => delta >= 0 || -num == delta,
             AdjustAction::Build { .. } => delta <= 0 || num == delta
-        } { return; }
+        } { return false; }
 
         // now we have to check if the given province is a valid one to build/
         // destroy in
@@ -36,32 +42,67 @@ impl Stpsyr {
         if !match action {
             AdjustAction::Disband => region.unit.as_ref()
                 .map_or(false, |u| u.owner == owner),
-            AdjustAction::Build { unit_type: t } => region.unit.is_none() &&
-                region.home_power.as_ref().map_or(false, |&ref p| *p == owner) &&
-                match t {
-                    UnitType::Army => !region.army_borders.is_empty(),
-                    UnitType::Fleet => !region.fleet_borders.is_empty()
-                }
-        } { return; }
+            AdjustAction::Build { unit_type: t, .. } => region.unit.is_none() &&
+                region.owner.as_ref() == Some(&owner) &&
+                self.is_home(region, &owner) && region.kind.allows(t)
+        } { return false; }
+
+        // fleets built in multi-coast provinces have to say which coast they
+        //   go on (other units don't get a coast)
+        let action = match action {
+            AdjustAction::Build { unit_type: UnitType::Fleet, coast } => {
+                let coasts = region.coasts();
+                AdjustAction::Build { unit_type: UnitType::Fleet, coast: match coast {
+                    _ if coasts.is_empty() => None,
+                    Some(coast) if coasts.contains(&coast) => Some(coast),
+                    _ => return false
+                } }
+            },
+            AdjustAction::Build { unit_type, .. } =>
+                AdjustAction::Build { unit_type, coast: None },
+            AdjustAction::Disband => AdjustAction::Disband
+        };
 
         // everything's good
+        self.adjusts.retain(|a| a.province != province);
         self.adjusts.push(Adjust {
             owner: owner,
             province: province,
             action: action
         });
+        true
     }
 
     // the publicly exposed function that is called once all adjusts have been
     //   added
     pub fn apply_adjusts(&mut self) {
+        // powers that didn't order enough disbands (including every power in
+        //   civil disorder) get the rest chosen for them
+        let (sc_counts, unit_counts) = (self.sc_counts(), self.unit_counts());
+        for (power, &units) in &unit_counts {
+            let centers = *sc_counts.get(power).unwrap_or(&0);
+            let ordered = self.adjusts.iter().filter(|a| a.owner == *power &&
+                matches!(a.action, AdjustAction::Disband)).count() as u32;
+            if units > centers + ordered {
+                let disbands = self.civil_disorder_disbands(power,
+                    (units - centers - ordered) as usize);
+                for province in disbands {
+                    self.adjusts.push(Adjust {
+                        owner: power.clone(),
+                        province,
+                        action: AdjustAction::Disband
+                    });
+                }
+            }
+        }
+
         for adjust in &self.adjusts {
             let region = self.map.iter_mut()
                 .find(|r| r.province == adjust.province).unwrap();
             match adjust.action {
                 AdjustAction::Disband => region.unit = None,
-                AdjustAction::Build { unit_type } => region.unit = Some(Unit {
-                    owner: adjust.owner.clone(), unit_type: unit_type
+                AdjustAction::Build { unit_type, coast } => region.unit = Some(Unit {
+                    owner: adjust.owner.clone(), unit_type, coast
                 })
             }
         }
@@ -70,4 +111,72 @@ impl Stpsyr {
         self.adjusts = vec![];
     }
 
+    // pick which units to disband for a power that didn't order its disbands:
+    //   the ones farthest from home go first, then fleets before armies, then
+    //   alphabetically by province
+    fn civil_disorder_disbands(&self, power: &Power, count: usize) -> Vec<Province> {
+        let ordered: Vec<&Province> = self.adjusts.iter()
+            .filter(|a| a.owner == *power).map(|a| &a.province).collect();
+        let mut candidates: Vec<(usize, UnitType, Province)> = self.map.iter()
+            .filter(|r| !ordered.contains(&&r.province))
+            .filter_map(|r| r.unit.as_ref().and_then(|u| if u.owner == *power {
+                Some((self.home_distance(&r.province, u.unit_type, power),
+                      u.unit_type, r.province.clone()))
+            } else { None })).collect();
+
+        candidates.sort_by(|a, b| b.0.cmp(&a.0)
+            .then((a.1 == UnitType::Army).cmp(&(b.1 == UnitType::Army)))
+            .then(a.2.name.cmp(&b.2.name)));
+        candidates.into_iter().take(count).map(|c| c.2).collect()
+    }
+
+    // whether an SC counts as home for a power under the game's build rule,
+    //   whether or not the power owns it right now
+    pub fn is_home(&self, region: &MapRegion, power: &Power) -> bool {
+        region.sc && match self.variant.rules.builds {
+            BuildRule::HomeCenters => region.home_power.as_ref() == Some(power),
+            BuildRule::Anywhere => true,
+            BuildRule::Conquest => region.home_power.as_ref() == Some(power) ||
+                region.conquered_by.contains(power)
+        }
+    }
+
+    // get every province a power could build in right now
+    pub fn build_sites(&self, power: &Power) -> Vec<Province> {
+        self.map.iter().filter(|r| r.unit.is_none() &&
+                r.owner.as_ref() == Some(power) && self.is_home(r, power))
+            .map(|r| r.province.clone()).collect()
+    }
+
+    // the number of moves it would take a unit to get to its nearest owned
+    //   home center, where armies are allowed to cross water (as if convoyed)
+    //   but fleets can only move as fleets
+    fn home_distance(&self, province: &Province, unit_type: UnitType,
+            power: &Power) -> usize {
+        let is_home = |r: &MapRegion, owned: bool| self.is_home(r, power) &&
+            (!owned || r.owner.as_ref() == Some(power));
+        let owned = self.map.iter().any(|r| is_home(r, true));
+
+        let mut seen: HashSet<Province> = HashSet::new();
+        let mut frontier = vec![province.clone()];
+        let mut distance = 0;
+        while !frontier.is_empty() {
+            let mut next = vec![];
+            for p in frontier {
+                if !seen.insert(p.clone()) { continue; }
+                let r = if let Some(r) = self.get_region(&p) { r } else { continue };
+                if is_home(r, owned) { return distance; }
+                next.extend(r.fleet_borders.iter().map(|b| b.to.province.clone()));
+                if unit_type == UnitType::Army {
+                    next.extend(r.army_borders.iter().cloned());
+                }
+            }
+            frontier = next;
+            distance += 1;
+        }
+
+        // there's no way home at all, so this unit is as far as it gets
+        usize::MAX
+    }
+
 }
@@ -0,0 +1,151 @@
+use stpsyr::types::*;
+use stpsyr::variant::open_canals;
+use stpsyr::validate::validate_map;
+
+// a MapBuilder creates a map in code, for tools that generate maps instead of
+//   loading them from a file
+// borders are always added in both directions, and coasts of multi-coast
+//   provinces are given with the usual "spa/nc" syntax
+// ex. MapBuilder::new()
+//         .province("lon", ProvinceKind::Coastal).sc("lon", Some(&england))
+//         .province("eng", ProvinceKind::Sea)
+//         .fleet_border("lon", "eng")
+//         .unit("lon", &england, UnitType::Fleet)
+//         .build()
+#[derive(Default)]
+pub struct MapBuilder {
+    map: Vec<MapRegion>,
+    problems: Vec<MapProblem>
+}
+
+impl MapBuilder {
+
+    pub fn new() -> MapBuilder {
+        MapBuilder::default()
+    }
+
+    // add a province (convoys are allowed in it if it's a sea)
+    pub fn province(&mut self, name: &str, kind: ProvinceKind) -> &mut MapBuilder {
+        let province = Province::from(name);
+        if self.map.iter().any(|r| r.province == province) {
+            self.problems.push(MapProblem::DuplicateProvince { province });
+            return self;
+        }
+        self.map.push(MapRegion {
+            full_name: province.name.clone(),
+            province,
+            aliases: vec![],
+            kind,
+            canal: false,
+            convoy: kind == ProvinceKind::Sea,
+            conquered_by: vec![],
+            sc: false,
+            owner: None,
+            home_power: None,
+            unit: None,
+            fleet_borders: vec![],
+            army_borders: vec![]
+        });
+        self
+    }
+
+    // set the full name of a province, and any other names it goes by
+    pub fn names(&mut self, name: &str, full_name: &str, aliases: &[&str])
+            -> &mut MapBuilder {
+        if let Some(r) = self.region(name) {
+            r.full_name = full_name.to_string();
+            r.aliases = aliases.iter().map(|a| a.to_string()).collect();
+        }
+        self
+    }
+
+    // make a province an SC, optionally a home center for (and owned by) a
+    //   power
+    pub fn sc(&mut self, name: &str, home_power: Option<&Power>) -> &mut MapBuilder {
+        if let Some(r) = self.region(name) {
+            r.sc = true;
+            r.home_power = home_power.cloned();
+            r.owner = home_power.cloned();
+        }
+        self
+    }
+
+    // make a province a canal
+    pub fn canal(&mut self, name: &str) -> &mut MapBuilder {
+        if let Some(r) = self.region(name) { r.canal = true; }
+        self
+    }
+
+    // choose whether fleets can convoy in a province
+    pub fn convoy(&mut self, name: &str, convoy: bool) -> &mut MapBuilder {
+        if let Some(r) = self.region(name) { r.convoy = convoy; }
+        self
+    }
+
+    // add an army border between two provinces
+    pub fn army_border(&mut self, a: &str, b: &str) -> &mut MapBuilder {
+        let (a, b) = (Location::from(a), Location::from(b));
+        self.half_border(&a, &b, UnitType::Army);
+        self.half_border(&b, &a, UnitType::Army);
+        self
+    }
+
+    // add a fleet border between two provinces (or coasts)
+    // ex. fleet_border("mao", "spa/nc")
+    pub fn fleet_border(&mut self, a: &str, b: &str) -> &mut MapBuilder {
+        let (a, b) = (Location::from(a), Location::from(b));
+        self.half_border(&a, &b, UnitType::Fleet);
+        self.half_border(&b, &a, UnitType::Fleet);
+        self
+    }
+
+    // place a starting unit (for fleets in multi-coast provinces, include the
+    //   coast); use Power::neutral() for a neutral garrison
+    pub fn unit(&mut self, name: &str, owner: &Power, unit_type: UnitType)
+            -> &mut MapBuilder {
+        let coast = Location::from(name).coast;
+        if let Some(r) = self.region(name) {
+            r.unit = Some(Unit { owner: owner.clone(), unit_type, coast });
+        }
+        self
+    }
+
+    // finish the map, returning every problem with it if there are any (see
+    //   validate_map)
+    pub fn build(&self) -> Result<Vec<MapRegion>, Vec<MapProblem>> {
+        let mut map = self.map.clone();
+        open_canals(&mut map);
+        let mut problems = self.problems.clone();
+        problems.extend(validate_map(&map));
+        if problems.is_empty() { Ok(map) } else { Err(problems) }
+    }
+
+    // add a border going one way, remembering if it's from a province that
+    //   doesn't exist
+    fn half_border(&mut self, from: &Location, to: &Location, unit_type: UnitType) {
+        let region = if let Some(r) = self.map.iter_mut()
+                .find(|r| r.province == from.province) { r } else {
+            self.problems.push(MapProblem::DanglingBorder {
+                from: to.clone(), to: from.clone(), unit_type
+            });
+            return;
+        };
+        match unit_type {
+            UnitType::Army => region.army_borders.push(to.province.clone()),
+            UnitType::Fleet => region.fleet_borders.push(Border {
+                from_coast: from.coast, to: to.clone()
+            })
+        }
+    }
+
+    // find a region that's been added, remembering if it hasn't
+    fn region(&mut self, name: &str) -> Option<&mut MapRegion> {
+        let province = Province::from(name);
+        let found = self.map.iter().position(|r| r.province == province);
+        if found.is_none() {
+            self.problems.push(MapProblem::UnknownProvince { province });
+        }
+        found.map(move |i| &mut self.map[i])
+    }
+
+}
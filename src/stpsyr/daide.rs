@@ -0,0 +1,368 @@
+use std::collections::{HashSet, VecDeque};
+use std::ops::Range;
+
+use stpsyr::types::*;
+
+// the provinces of the standard map whose DAIDE names aren't just their own
+//   names in uppercase
+const NAMES: &[(&str, &str)] = &[("lyo", "GOL"), ("eng", "ECH"), ("bot", "GOB")];
+
+// the DAIDE names of coasts
+const COASTS: &[(char, &str)] = &[
+    ('n', "NCS"), ('s', "SCS"), ('e', "ECS"), ('w', "WCS")
+];
+
+impl Stpsyr {
+
+    // read an order written in DAIDE, checking that its powers and provinces
+    //   exist (but not that it's allowed, see add_daide)
+    // the route of a convoyed move is skipped, since adjudication finds it
+    // ex. "( RUS FLT ( STP SCS ) ) MTO GOB", "( ENG AMY LON ) CTO NWY VIA ( NTH )"
+    pub fn parse_daide(&self, s: &str) -> Result<DaideOrder, ParseError> {
+        let items = read(s)?;
+        let unparseable = |span: Range<usize>|
+            ParseError { kind: ParseErrorKind::Unparseable, span };
+
+        // "ENG WVE" is the only order that isn't about a unit
+        if let [Daide::Word(ref power, ref span), Daide::Word(ref wve, _)] = items[..] {
+            if wve == "WVE" { return self.daide_power(power, span).map(DaideOrder::Waive); }
+        }
+
+        let (unit, rest) = match items.split_first() {
+            Some((unit, rest)) => (unit, rest),
+            None => return Err(ParseError::whole(ParseErrorKind::Unparseable, s))
+        };
+        let (owner, unit_type, location) = self.daide_unit(unit)?;
+        let keyword = |i: usize| rest.get(i).and_then(Daide::word);
+        let order = |action| DaideOrder::Order(Order {
+            owner: owner.clone(),
+            province: location.province.clone(),
+            unit_type,
+            coast: location.coast,
+            action,
+            resolution: false,
+            state: OrderState::UNRESOLVED,
+            id: 0
+        });
+        let retreat = |action| DaideOrder::Retreat(Retreat {
+            owner: owner.clone(),
+            province: location.province.clone(),
+            unit_type,
+            coast: location.coast,
+            action
+        });
+        let adjust = |action| Adjust {
+            owner: owner.clone(),
+            province: location.province.clone(),
+            action
+        };
+
+        Ok(match (keyword(0), rest.len()) {
+            (Some("HLD"), 1) => order(Action::Hold),
+            (Some("MTO"), 2) => order(Action::Move {
+                to: self.daide_location(&rest[1])?, convoyed: false
+            }),
+            (Some("SUP"), 2) => {
+                let (_, supported, to) = self.daide_unit(&rest[1])?;
+                order(Action::SupportHold { to: to.province, supported: Some(supported) })
+            },
+            (Some("SUP"), 4) if keyword(2) == Some("MTO") => {
+                let (_, supported, from) = self.daide_unit(&rest[1])?;
+                order(Action::SupportMove {
+                    from: from.province,
+                    to: self.daide_location(&rest[3])?.province,
+                    supported: Some(supported)
+                })
+            },
+            (Some("CVY"), 4) if keyword(2) == Some("CTO") => order(Action::Convoy {
+                from: self.daide_unit(&rest[1])?.2.province,
+                to: self.daide_location(&rest[3])?.province
+            }),
+            (Some("CTO"), 2) => order(Action::Move {
+                to: self.daide_location(&rest[1])?, convoyed: true
+            }),
+            (Some("CTO"), 4) if keyword(2) == Some("VIA") &&
+                    rest[3].word().is_none() => order(Action::Move {
+                to: self.daide_location(&rest[1])?, convoyed: true
+            }),
+            (Some("RTO"), 2) => retreat(RetreatAction::Move {
+                to: self.daide_location(&rest[1])?
+            }),
+            (Some("DSB"), 1) => retreat(RetreatAction::Disband),
+            (Some("BLD"), 1) => DaideOrder::Adjust(adjust(AdjustAction::Build {
+                unit_type, coast: location.coast
+            })),
+            (Some("REM"), 1) => DaideOrder::Remove(adjust(AdjustAction::Disband), unit_type),
+            _ => return Err(unparseable(match (rest.first(), rest.last()) {
+                (Some(first), Some(last)) => first.span().start..last.span().end,
+                _ => unit.span()
+            }))
+        })
+    }
+
+    // read an order written in DAIDE and add it, if it's for the current
+    //   phase and allowed
+    // DAIDE has no way to warn about anything, so an order for the wrong type
+    //   of unit is rejected unless the rules say to ignore that
+    pub fn add_daide(&mut self, s: &str) -> Result<(), ParseError> {
+        let order = self.parse_daide(s)?;
+        let error = |kind| Err(ParseError::whole(kind, s));
+
+        let wrong_type = match order {
+            DaideOrder::Order(ref o) => self.get_unit(&o.province)
+                .is_some_and(|u| u.unit_type != o.unit_type),
+            DaideOrder::Retreat(ref r) => self.dislodged.iter()
+                .any(|(p, u)| *p == r.province && u.unit_type != r.unit_type),
+            DaideOrder::Remove(ref a, unit_type) => self.get_unit(&a.province)
+                .is_some_and(|u| u.unit_type != unit_type),
+            _ => false
+        };
+        if wrong_type && self.variant.rules.unit_types != UnitTypeCheck::Ignore {
+            return error(ParseErrorKind::WrongUnitType);
+        }
+
+        let accepted = match (order, self.phase) {
+            (DaideOrder::Order(o), Phase::SpringDiplomacy) |
+            (DaideOrder::Order(o), Phase::FallDiplomacy) =>
+                self.add_order(o.owner, o.province, o.action),
+            (DaideOrder::Retreat(r), Phase::SpringRetreats) |
+            (DaideOrder::Retreat(r), Phase::FallRetreats) =>
+                self.add_retreat(r.owner, r.province, r.action),
+            (DaideOrder::Adjust(a), Phase::Builds) |
+            (DaideOrder::Remove(a, _), Phase::Builds) =>
+                self.add_adjust(a.owner, a.province, a.action),
+            (DaideOrder::Waive(power), Phase::Builds) =>
+                self.orderer(&power).is_some(),
+            _ => false
+        };
+        if accepted { Ok(()) } else { error(ParseErrorKind::Rejected) }
+    }
+
+    // write an order in DAIDE, using the units on the board for the ones it
+    //   only names by province (so this is None if one of them isn't there)
+    // convoyed moves are written with the route of the fleets currently
+    //   ordered to convoy them (so this is None if there isn't one yet)
+    // ex. "( FRA AMY PAR ) SUP ( FRA AMY MAR ) MTO BUR"
+    pub fn to_daide(&self, order: &DaideOrder) -> Option<String> {
+        let unit_at = |province: &Province| self.get_region(province)
+            .and_then(|r| r.unit.as_ref().map(|u| daide_unit(&u.owner, u.unit_type,
+                &Location { province: province.clone(), coast: u.coast })));
+        let location = |province: &Province, coast| daide_location(&Location {
+            province: province.clone(), coast
+        });
+
+        Some(match *order {
+            DaideOrder::Order(ref o) => {
+                let unit = daide_unit(&o.owner, o.unit_type,
+                    &Location { province: o.province.clone(), coast: o.coast });
+                match o.action {
+                    Action::Hold => format!("{} HLD", unit),
+                    Action::Move { ref to, convoyed: false } =>
+                        format!("{} MTO {}", unit, daide_location(to)),
+                    Action::Move { ref to, convoyed: true } => {
+                        let route: String = self.convoy_route(&o.province, &to.province)?
+                            .iter().map(|p| location(p, None) + " ").collect();
+                        format!("{} CTO {} VIA ( {})", unit, daide_location(to), route)
+                    },
+                    Action::SupportHold { ref to, .. } =>
+                        format!("{} SUP {}", unit, unit_at(to)?),
+                    Action::SupportMove { ref from, ref to, .. } => format!("{} SUP {} MTO {}",
+                        unit, unit_at(from)?, location(to, None)),
+                    Action::Convoy { ref from, ref to } => format!("{} CVY {} CTO {}",
+                        unit, unit_at(from)?, location(to, None))
+                }
+            },
+            DaideOrder::Retreat(ref r) => {
+                let unit = daide_unit(&r.owner, r.unit_type,
+                    &Location { province: r.province.clone(), coast: r.coast });
+                match r.action {
+                    RetreatAction::Disband => format!("{} DSB", unit),
+                    RetreatAction::Move { ref to } =>
+                        format!("{} RTO {}", unit, daide_location(to))
+                }
+            },
+            DaideOrder::Adjust(ref a) => match a.action {
+                AdjustAction::Build { unit_type, coast } => format!("{} BLD",
+                    daide_unit(&a.owner, unit_type, &Location {
+                        province: a.province.clone(), coast
+                    })),
+                AdjustAction::Disband => format!("{} REM", unit_at(&a.province)?)
+            },
+            DaideOrder::Remove(ref a, _) => format!("{} REM", unit_at(&a.province)?),
+            DaideOrder::Waive(ref power) => format!("{} WVE", daide_power(power))
+        })
+    }
+
+    // find the power a DAIDE power name is for (the first three letters of
+    //   its name)
+    fn daide_power(&self, name: &str, span: &Range<usize>) -> Result<Power, ParseError> {
+        self.variant.powers.iter().find(|p| daide_power(&p.power) == name)
+            .map(|p| p.power.clone())
+            .ok_or(ParseError { kind: ParseErrorKind::UnknownPower, span: span.clone() })
+    }
+
+    // read a DAIDE unit
+    // ex. "( ENG FLT LON )"
+    fn daide_unit(&self, unit: &Daide) -> Result<(Power, UnitType, Location), ParseError> {
+        let unparseable = ParseError {
+            kind: ParseErrorKind::Unparseable, span: unit.span()
+        };
+        if let Daide::List(ref items, _) = *unit {
+            if let [Daide::Word(ref power, ref span), Daide::Word(ref unit_type, _),
+                    ref location] = items[..] {
+                let unit_type = match &unit_type[..] {
+                    "AMY" => UnitType::Army,
+                    "FLT" => UnitType::Fleet,
+                    _ => return Err(unparseable)
+                };
+                return Ok((self.daide_power(power, span)?, unit_type,
+                    self.daide_location(location)?));
+            }
+        }
+        Err(unparseable)
+    }
+
+    // read a DAIDE province, with or without a coast
+    // ex. "LON", "( STP NCS )"
+    fn daide_location(&self, location: &Daide) -> Result<Location, ParseError> {
+        let (name, span, coast) = match *location {
+            Daide::Word(ref name, ref span) => (name, span, None),
+            Daide::List(ref items, ref span) => match items[..] {
+                [Daide::Word(ref name, ref span), Daide::Word(ref coast, _)] =>
+                    match COASTS.iter().find(|c| c.1 == coast) {
+                        Some(c) => (name, span, Some(c.0)),
+                        None => return Err(ParseError {
+                            kind: ParseErrorKind::Unparseable, span: span.clone()
+                        })
+                    },
+                _ => return Err(ParseError {
+                    kind: ParseErrorKind::Unparseable, span: span.clone()
+                })
+            }
+        };
+        let province = Province::from(NAMES.iter().find(|n| n.1 == name)
+            .map_or(name.to_lowercase(), |n| n.0.to_string()));
+        if self.get_region(&province).is_none() {
+            return Err(ParseError {
+                kind: ParseErrorKind::UnknownProvince { suggestions: vec![] },
+                span: span.clone()
+            });
+        }
+        Ok(Location { province, coast })
+    }
+
+    // the provinces of the fleets convoying an army from one province to
+    //   another, in order (None if there aren't enough of them)
+    fn convoy_route(&self, from: &Province, to: &Province) -> Option<Vec<Province>> {
+        let convoying: Vec<&Province> = self.orders.iter().filter(|o|
+            match o.action {
+                Action::Convoy { from: ref f, to: ref t } => f == from && t == to,
+                _ => false
+            }).map(|o| &o.province).collect();
+        let borders = |a: &Province, b: &Province| self.get_region(a)
+            .is_some_and(|r| r.fleet_borders.iter().any(|l| l.to.province == *b));
+
+        // breadth first, so the route is as short as it can be
+        let mut queue = VecDeque::new();
+        queue.push_back((from.clone(), vec![]));
+        let mut seen = HashSet::new();
+        while let Some((province, route)) = queue.pop_front() {
+            if !route.is_empty() && borders(&province, to) { return Some(route); }
+            for &fleet in &convoying {
+                if borders(&province, fleet) && seen.insert(fleet.clone()) {
+                    let mut route = route.clone();
+                    route.push(fleet.clone());
+                    queue.push_back((fleet.clone(), route));
+                }
+            }
+        }
+        None
+    }
+
+}
+
+// the DAIDE name of a power
+// ex. ENG
+fn daide_power(power: &Power) -> String {
+    power.name.chars().take(3).collect::<String>().to_uppercase()
+}
+
+// write a location in DAIDE
+// ex. "LON", "( STP NCS )"
+fn daide_location(location: &Location) -> String {
+    let name = NAMES.iter().find(|n| n.0 == location.province.name)
+        .map_or(location.province.name.to_uppercase(), |n| n.1.to_string());
+    match location.coast.and_then(|c| COASTS.iter().find(|n| n.0 == c)) {
+        Some(coast) => format!("( {} {} )", name, coast.1),
+        None => name
+    }
+}
+
+// write a unit in DAIDE
+// ex. "( ENG FLT LON )"
+fn daide_unit(owner: &Power, unit_type: UnitType, location: &Location) -> String {
+    format!("( {} {} {} )", daide_power(owner), match unit_type {
+        UnitType::Army => "AMY",
+        UnitType::Fleet => "FLT"
+    }, daide_location(location))
+}
+
+// a DAIDE message is made of words and bracketed lists of them, which are
+//   kept with the columns they came from
+enum Daide {
+    Word(String, Range<usize>),
+    List(Vec<Daide>, Range<usize>)
+}
+
+impl Daide {
+
+    fn word(&self) -> Option<&str> {
+        match *self {
+            Daide::Word(ref word, _) => Some(word),
+            Daide::List(..) => None
+        }
+    }
+
+    fn span(&self) -> Range<usize> {
+        match *self {
+            Daide::Word(_, ref span) | Daide::List(_, ref span) => span.clone()
+        }
+    }
+
+}
+
+// split a DAIDE message into words and lists (words are read in uppercase)
+fn read(s: &str) -> Result<Vec<Daide>, ParseError> {
+    let chars: Vec<char> = s.chars().collect();
+    let unparseable = |i: usize|
+        Err(ParseError { kind: ParseErrorKind::Unparseable, span: i..i + 1 });
+
+    // the lists that have been opened, and the columns they were opened at
+    let mut lists: Vec<(Vec<Daide>, usize)> = vec![(vec![], 0)];
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '(' => lists.push((vec![], i)),
+            ')' => {
+                if lists.len() == 1 { return unparseable(i); }
+                let (list, start) = lists.pop().unwrap();
+                lists.last_mut().unwrap().0.push(Daide::List(list, start..i + 1));
+            },
+            c if c.is_whitespace() => {},
+            _ => {
+                let start = i;
+                while i + 1 < chars.len() && !chars[i + 1].is_whitespace() &&
+                        chars[i + 1] != '(' && chars[i + 1] != ')' {
+                    i += 1;
+                }
+                let word: String = chars[start..i + 1].iter().collect();
+                lists.last_mut().unwrap().0.push(Daide::Word(word.to_uppercase(),
+                    start..i + 1));
+            }
+        }
+        i += 1;
+    }
+
+    if lists.len() > 1 { return unparseable(lists.pop().unwrap().1); }
+    Ok(lists.pop().unwrap().0)
+}
@@ -0,0 +1,112 @@
+use std::fmt;
+
+use stpsyr::types::*;
+
+// an order document is plain text, one line at a time:
+// - lines starting with "//" are comments, and blank lines are skipped
+// - "= SpringDiplomacy 1901" starts a new turn for that phase and year (a
+//   document that only has one turn doesn't need it)
+// - a line that isn't indented is a heading for a power (by name, adjective
+//   or abbreviation, with an optional ":" after it)
+// - an indented line is an order for the power in the last heading, in
+//   anything Stpsyr::parse understands
+// ex. = FallDiplomacy 1901
+//     // England is going for Norway
+//     England
+//         F nth C A yor-nwy
+//         A yor-nwy via convoy
+//     France:
+//         A par-bur
+
+const PHASES: &[Phase] = &[Phase::SpringDiplomacy, Phase::SpringRetreats,
+    Phase::FallDiplomacy, Phase::FallRetreats, Phase::Builds, Phase::GameOver];
+
+impl Stpsyr {
+
+    // read an order document (see above)
+    pub fn read_document(&self, text: &str) -> Result<OrderDocument, DocumentError> {
+        let mut turns = vec![Turn::default()];
+        for (idx, line) in text.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with("//") { continue; }
+
+            let turn = turns.last_mut().unwrap();
+            if let Some(header) = trimmed.strip_prefix('=') {
+                let phase = parse_phase(header)
+                    .ok_or(DocumentError::BadPhase { line: idx + 1 })?;
+                if *turn == Turn::default() {
+                    turn.phase = Some(phase);
+                } else {
+                    turns.push(Turn { phase: Some(phase), submissions: vec![] });
+                }
+            } else if line.starts_with(char::is_whitespace) {
+                turn.submissions.last_mut()
+                    .ok_or(DocumentError::MissingPower { line: idx + 1 })?
+                    .orders.push(trimmed.to_string());
+            } else {
+                let name = trimmed.trim_end_matches(':').trim();
+                let power = self.variant.find_power(name)
+                    .ok_or_else(|| DocumentError::UnknownPower {
+                        line: idx + 1, name: name.to_string()
+                    })?;
+                turn.submissions.push(Submission { power, orders: vec![] });
+            }
+        }
+
+        turns.retain(|t| *t != Turn::default());
+        Ok(OrderDocument { turns })
+    }
+
+    // give every power its orders from a document and adjudicate them, one
+    //   turn after another, returning what became of each power's orders
+    //   (see parse)
+    // a turn for a phase other than the current one stops everything, but
+    //   the turns before it have been played by then
+    pub fn play_document(&mut self, document: &OrderDocument)
+            -> Result<Vec<(Power, Vec<Diagnostic>)>, DocumentError> {
+        let mut diagnostics = vec![];
+        for turn in &document.turns {
+            if let Some((phase, year)) = turn.phase {
+                if (phase, year) != (self.phase, self.year) {
+                    return Err(DocumentError::WrongPhase { phase, year });
+                }
+            }
+            for submission in &turn.submissions {
+                diagnostics.push((submission.power.clone(),
+                    self.parse(&submission.power, submission.orders.join("\n"))));
+            }
+            self.apply();
+        }
+        Ok(diagnostics)
+    }
+
+}
+
+// write an order document back out, in the same format it's read in
+impl fmt::Display for OrderDocument {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (idx, turn) in self.turns.iter().enumerate() {
+            if idx > 0 { writeln!(f)?; }
+            if let Some((phase, year)) = turn.phase {
+                writeln!(f, "= {:?} {}", phase, year)?;
+            }
+            for submission in &turn.submissions {
+                writeln!(f, "{:?}", submission.power)?;
+                for order in &submission.orders {
+                    writeln!(f, "    {}", order)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// read a phase and year, ignoring case and spaces in the phase
+// ex. "SpringDiplomacy 1901", "fall retreats 1902"
+fn parse_phase(header: &str) -> Option<(Phase, i32)> {
+    let mut words: Vec<&str> = header.split_whitespace().collect();
+    let year = words.pop()?.parse().ok()?;
+    let name = words.concat().to_lowercase();
+    PHASES.iter().find(|p| format!("{:?}", p).to_lowercase() == name)
+        .map(|&p| (p, year))
+}
@@ -0,0 +1,112 @@
+use stpsyr::types::*;
+
+impl Stpsyr {
+
+    // put a draw or concession to a vote, returning its index in
+    //   self.proposals (the proposer automatically votes for it)
+    // a power named more than once in a draw is only counted once
+    pub fn propose(&mut self, proposer: &Power, kind: ProposalKind) -> Option<usize> {
+        if self.phase == Phase::GameOver { return None; }
+
+        // only powers that can give orders get to propose things
+        if self.power_status(proposer) != Some(PowerStatus::Active) { return None; }
+
+        // everyone named in a proposal has to still be in the game
+        let survivors = self.survivors();
+        if !match kind {
+            ProposalKind::DrawAll => true,
+            ProposalKind::Draw { ref powers } => !powers.is_empty() &&
+                powers.iter().all(|p| survivors.contains(p)),
+            ProposalKind::Concession { ref to } => survivors.contains(to)
+        } { return None; }
+
+        let kind = match kind {
+            ProposalKind::Draw { powers } => {
+                let mut unique: Vec<Power> = vec![];
+                for power in powers {
+                    if !unique.contains(&power) { unique.push(power); }
+                }
+                ProposalKind::Draw { powers: unique }
+            },
+            kind => kind
+        };
+
+        self.proposals.push(Proposal {
+            kind,
+            proposer: proposer.clone(),
+            phase: self.phase,
+            year: self.year,
+            votes: vec![]
+        });
+        let id = self.proposals.len() - 1;
+        self.vote(proposer, id, true);
+        Some(id)
+    }
+
+    // vote on a proposal (a power may change its vote until the phase ends)
+    // a single veto blocks the proposal for as long as it stands, and a
+    //   unanimous vote ends the game; proposals stay in self.proposals until
+    //   the phase ends either way, so their ids don't change
+    pub fn vote(&mut self, power: &Power, id: usize, accept: bool) {
+        if self.phase == Phase::GameOver || id >= self.proposals.len() { return; }
+        if self.power_status(power) != Some(PowerStatus::Active) { return; }
+
+        {
+            let votes = &mut self.proposals[id].votes;
+            votes.retain(|(p, _)| p != power);
+            votes.push((power.clone(), accept));
+        }
+        if !accept { return; }
+
+        // powers in civil disorder don't get a vote, so unanimity only counts
+        //   the active ones
+        let voters: Vec<Power> = self.powers.iter()
+            .filter(|(_, s)| *s == PowerStatus::Active)
+            .map(|(p, _)| p.clone()).collect();
+        let unanimous = voters.iter().all(|v| self.proposals[id].votes.iter()
+            .any(|(p, accept)| p == v && *accept));
+
+        if unanimous {
+            let result = match self.proposals[id].kind {
+                ProposalKind::DrawAll => GameResult::Draw {
+                    powers: self.survivors(), year: self.year
+                },
+                ProposalKind::Draw { ref powers } => GameResult::Draw {
+                    powers: powers.clone(), year: self.year
+                },
+                ProposalKind::Concession { ref to } => GameResult::Concession {
+                    to: to.clone(), year: self.year
+                }
+            };
+            self.end_game(result);
+        }
+    }
+
+    // end the game with the given result; nothing can be ordered afterwards
+    pub fn end_game(&mut self, result: GameResult) {
+        self.result = Some(result);
+        self.phase = Phase::GameOver;
+        self.proposals = vec![];
+        self.orders = vec![];
+        self.retreats = vec![];
+        self.adjusts = vec![];
+    }
+
+    // every power that hasn't been eliminated (including those in civil
+    //   disorder)
+    pub fn survivors(&self) -> Vec<Power> {
+        self.powers.iter()
+            .filter(|(_, s)| !matches!(s, PowerStatus::Eliminated { .. }))
+            .map(|(p, _)| p.clone()).collect()
+    }
+
+}
+
+impl Proposal {
+
+    // whether anyone is vetoing this proposal
+    pub fn rejected(&self) -> bool {
+        self.votes.iter().any(|&(_, accept)| !accept)
+    }
+
+}
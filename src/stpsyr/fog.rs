@@ -0,0 +1,69 @@
+use std::collections::HashSet;
+
+use stpsyr::types::*;
+
+impl Stpsyr {
+
+    // get every province a power can see: the ones its units are in and the
+    //   SCs it owns, plus everything bordering those
+    pub fn visible_provinces(&self, power: &Power) -> HashSet<Province> {
+        let mut visible = HashSet::new();
+        for r in &self.map {
+            let occupied = r.unit.as_ref().is_some_and(|u| u.owner == *power);
+            let owned = r.sc && r.owner.as_ref() == Some(power);
+            if occupied || owned {
+                visible.insert(r.province.clone());
+                visible.extend(r.fleet_borders.iter().map(|b| b.to.province.clone()));
+                visible.extend(r.army_borders.iter().cloned());
+            }
+        }
+        visible
+    }
+
+    // get the game as a power sees it: everyone else's orders for the current
+    //   phase are removed, and with fog of war, so are units, dislodged units
+    //   and order results out of its sight, and provinces out of its sight
+    //   that aren't SCs go back to their starting owners (since they change
+    //   hands whenever a unit goes through them)
+    // the view is a game of its own, so it can be rendered, serialized and
+    //   sent to that power's player like any other
+    pub fn view(&self, power: &Power) -> Stpsyr {
+        let mut view = self.clone();
+
+        // orders for the current phase are secret either way
+        view.orders.retain(|o| o.owner == *power);
+        view.retreats.retain(|r| r.owner == *power);
+        view.adjusts.retain(|a| a.owner == *power);
+        view.dependencies = vec![];
+
+        if !self.variant.rules.fog_of_war { return view; }
+
+        let visible = self.visible_provinces(power);
+        let sees = |p: &Province| visible.contains(p);
+
+        for r in &mut view.map {
+            if sees(&r.province) { continue; }
+            r.unit = None;
+            if !r.sc {
+                r.owner = self.variant.map.iter().find(|v| v.province == r.province)
+                    .and_then(|v| v.owner.clone());
+            }
+        }
+        view.dislodged.retain(|(p, _)| sees(p));
+        view.contested.retain(|p| sees(p));
+
+        // results of the last diplomacy phase can be seen if they involved
+        //   anywhere in sight
+        view.resolved.retain(|o| o.owner == *power || sees(&o.province) ||
+            match o.action {
+                Action::Hold => false,
+                Action::Move { ref to, .. } => sees(&to.province),
+                Action::SupportHold { ref to, .. } => sees(to),
+                Action::SupportMove { ref from, ref to, .. } |
+                Action::Convoy { ref from, ref to } => sees(from) || sees(to)
+            });
+
+        view
+    }
+
+}
@@ -0,0 +1,108 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use stpsyr::types::*;
+
+// the locales that come with stpsyr
+const BUILTIN: &[&str] = &[
+    include_str!("../../data/locales/english.locale"),
+    include_str!("../../data/locales/german.locale"),
+    include_str!("../../data/locales/french.locale")
+];
+
+// each keyword, as it's given in a locale file and as the order grammars in
+//   parse.rs understand it
+const KEYWORDS: &[(&str, Keyword)] = &[
+    ("army", Keyword::Army), ("fleet", Keyword::Fleet),
+    ("hold", Keyword::Hold), ("move", Keyword::Move),
+    ("support", Keyword::Support), ("convoy", Keyword::Convoy),
+    ("via", Keyword::Via), ("coast", Keyword::Coast),
+    ("retreat", Keyword::Retreat), ("disband", Keyword::Disband),
+    ("build", Keyword::Build), ("destroy", Keyword::Destroy),
+    ("waive", Keyword::Waive)
+];
+
+impl Locale {
+
+    // load a locale file, which looks like this:
+    //
+    //     # comments start with a hash
+    //     name: German
+    //     support: u, unterstützt, unterstuetzt
+    //     ignore: der, die, das
+    //     alias: mun, München, Muenchen
+    //
+    // with a line for any keyword (army, fleet, hold, move, support, convoy,
+    //   via, coast, retreat, disband, build, destroy or waive) giving the
+    //   words that mean it, ignore lines for words to skip, and alias lines
+    //   giving more names for a province (none of which are case sensitive)
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Locale> {
+        let path = path.as_ref();
+        let mut text = String::new();
+        File::open(path)?.read_to_string(&mut text)?;
+        Locale::read(&text).map_err(|msg| io::Error::new(
+            io::ErrorKind::InvalidData, format!("{}: {}", path.display(), msg)))
+    }
+
+    // read a locale from what would be in a locale file (see load)
+    pub fn read(text: &str) -> Result<Locale, String> {
+        let mut locale = Locale::default();
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') { continue; }
+
+            let (key, value) = if let Some(idx) = line.find(':') {
+                (line[..idx].trim(), line[idx+1..].trim())
+            } else { return Err(format!("bad line \"{}\"", line)); };
+            let mut words = value.split(',').map(|w| w.trim().to_lowercase())
+                .filter(|w| !w.is_empty());
+
+            match key {
+                "name" => locale.name = value.to_string(),
+                "ignore" => locale.ignored.extend(words),
+                "alias" => {
+                    let province = Province::from(words.next()
+                        .ok_or_else(|| format!("bad alias \"{}\"", value))?);
+                    locale.aliases.extend(words.map(|w| (province.clone(), w)));
+                },
+                _ => match KEYWORDS.iter().find(|k| k.0 == key) {
+                    Some(&(_, keyword)) =>
+                        locale.keywords.extend(words.map(|w| (keyword, w))),
+                    None => return Err(format!("unknown key \"{}\"", key))
+                }
+            }
+        }
+
+        if locale.name.is_empty() { return Err(String::from("missing name")); }
+        Ok(locale)
+    }
+
+    // one of the locales that come with stpsyr (see data/locales), by name
+    // ex. "English", "german"
+    pub fn builtin(name: &str) -> Option<Locale> {
+        let name = name.trim().to_lowercase();
+        BUILTIN.iter().map(|text| Locale::read(text).unwrap())
+            .find(|locale| locale.name.to_lowercase() == name)
+    }
+
+    // the locale Stpsyr::parse uses
+    pub fn english() -> Locale {
+        Locale::builtin("english").unwrap()
+    }
+
+    // what a word (in lowercase) means in orders, if it's a keyword
+    pub fn keyword(&self, word: &str) -> Option<Keyword> {
+        self.keywords.iter().find(|k| k.1 == word).map(|k| k.0)
+    }
+
+}
+
+impl Keyword {
+
+    // the word for a keyword that the order grammars understand
+    pub fn word(self) -> &'static str {
+        KEYWORDS.iter().find(|k| k.1 == self).unwrap().0
+    }
+
+}
@@ -1,70 +1,116 @@
+use std::mem;
+
 use stpsyr::types::*;
 
 impl Stpsyr {
 
-    // the publicly exposed function to modify self.orders
-    pub fn add_order(&mut self, owner: Power, province: Province, action: Action) {
+    // the publicly exposed function to modify self.orders, returning whether the
+    //   order was accepted (in which case it replaces any order the unit
+    //   already had)
+    pub fn add_order(&mut self, owner: Power, province: Province, action: Action)
+            -> bool {
         match self.phase {
             Phase::SpringDiplomacy | Phase::FallDiplomacy => {},
             _ => panic!("add_order called during non-diplomacy phase")
         };
 
+        // eliminated powers (and powers in civil disorder) can't give orders
+        let owner = if let Some(owner) = self.orderer(&owner) { owner }
+            else { return false; };
+
         // there has to be a unit here to order it
         let unit = if let Some(unit) = self.get_unit(&province) { unit }
-            else { return; };
+            else { return false; };
+
+        // armies don't care about coasts, and supports that leave out the type
+        //   of the unit they support (ex. "A mos S war") get it from the board
+        let unit_type = |p: &Province| self.get_unit(p).map(|u| u.unit_type);
+        let action = match action {
+            Action::Move { to, convoyed } if unit.unit_type == UnitType::Army =>
+                Action::Move { to: Location::from(to.province), convoyed },
+            Action::SupportHold { to, supported: None } =>
+                Action::SupportHold { supported: unit_type(&to), to },
+            Action::SupportMove { from, to, supported: None } =>
+                Action::SupportMove { supported: unit_type(&from), from, to },
+            action => action
+        };
 
-        let (is_move, convoyed) = match action {
+        let convoyed = match action {
             Action::Move { ref to, convoyed } => {
                 // let's do a quick check here: unit can't move to itself
-                if province == *to { return; }
-                (true, convoyed)
+                if province == to.province { return false; }
+                convoyed
             },
-            Action::SupportMove { ref from, ref to } => {
+            Action::SupportMove { ref from, ref to, .. } => {
                 // another quick check: can't support yourself or a non-move
-                if province == *from || province == *to || *from == *to { return; }
-                (false, false)
+                if province == *from || province == *to || *from == *to { return false; }
+                false
             }
-            _ => (false, false)
+            _ => false
         }; // NOTE use this better
 
-        // TODO ensure convoying fleets are on open water
+        // only fleets can convoy, and only where the map allows it
+        if let Action::Convoy { .. } = action {
+            if unit.unit_type != UnitType::Fleet ||
+                    !self.get_region(&province).unwrap().convoy { return false; }
+        }
+
         // TODO ensure convoying armies actually have the requisite fleets
         // (c.f. DATC 6.D.32)
 
+        // nobody can support a neutral garrison
+        if match action {
+            Action::SupportHold { ref to, .. } => self.get_unit(to),
+            Action::SupportMove { ref from, .. } => self.get_unit(from),
+            _ => None
+        }.is_some_and(|u| u.owner.is_neutral()) { return false; }
+
         // can't convoy a fleet
-        if convoyed && unit.unit_type == UnitType::Fleet { return; }
+        if convoyed && unit.unit_type == UnitType::Fleet { return false; }
 
         // can't order a unit that's not yours
-        if unit.owner != owner { return; }
+        if unit.owner != owner { return false; }
+
+        // nothing can go into (or support into) an impassable province
+        if match action {
+            Action::Move { ref to, .. } => !self.is_passable(&to.province),
+            Action::SupportHold { ref to, .. } |
+            Action::SupportMove { ref to, .. } => !self.is_passable(to),
+            _ => false
+        } { return false; }
 
-        // can't order to a province you can't reach
+        // can't order to a province you can't reach (fleets have to move to
+        //   the right coast, but supports don't care which coast they're to)
+        let r = self.get_region(&province).unwrap();
+        let moves: Vec<Location> = match unit.unit_type {
+            UnitType::Army => r.army_borders.iter().cloned().map(Location::from)
+                .collect(),
+            UnitType::Fleet => r.fleet_moves(unit.coast)
+        };
         if !convoyed && match action {
-            Action::Move { ref to, .. } |
-            Action::SupportHold { ref to } |
-            Action::SupportMove { ref to, .. } => {
-                let r = self.get_region(&province).unwrap();
-                !match unit.unit_type {
-                    UnitType::Army => r.army_borders.clone(),
-                    UnitType::Fleet => r.fleet_borders.clone().into_iter()
-                        .filter(|p|
-                            p.from_coast == r.province.coast &&
-                            (!is_move || p.coast == to.coast))
-                        .collect()
-                }.contains(to)
-            },
+            Action::Move { ref to, .. } => !moves.contains(to),
+            Action::SupportHold { ref to, .. } |
+            Action::SupportMove { ref to, .. } =>
+                !moves.iter().any(|l| l.province == *to),
             _ => false
-        } { return; }
+        } { return false; }
 
         // all checks pass
-        let id = self.orders.len();
-        self.orders.push(Order {
+        let id = self.orders.iter().position(|o| o.province == province)
+            .unwrap_or(self.orders.len());
+        let order = Order {
             owner: owner,
             province: province,
+            unit_type: unit.unit_type,
+            coast: unit.coast,
             action: action,
             resolution: false,
             state: OrderState::UNRESOLVED,
             id: id
-        });
+        };
+        if id < self.orders.len() { self.orders[id] = order; }
+        else { self.orders.push(order); }
+        true
     }
 
     // this is the publicly exposed function that is called once all orders
@@ -85,7 +131,7 @@ impl Stpsyr {
         println!("{:?} {}: {:?}", self.phase, self.year, self.map);
 
         self.next_phase();
-        self.orders = vec![];
+        self.resolved = mem::take(&mut self.orders);
     }
 
 }
@@ -1,19 +1,83 @@
+use std::cmp;
+use std::mem;
+use std::ops::Range;
+use std::str::FromStr;
+
 use stpsyr::types::*;
 
 extern crate bincode;
 
 impl Stpsyr {
 
-    // parse orders as a string and apply them
-    pub fn parse(&mut self, power: &Power, orders: String) {
-        match self.phase {
-            Phase::SpringDiplomacy | Phase::FallDiplomacy =>
-                self.parse_orders(power, orders),
-            Phase::SpringRetreats | Phase::FallRetreats =>
-                self.parse_retreats(power, orders),
-            Phase::Builds =>
-                self.parse_adjusts(power, orders)
+    // parse orders as a string and add them, one per line, returning what
+    //   became of every line that isn't blank (see Diagnostic)
+    pub fn parse(&mut self, power: &Power, orders: String) -> Vec<Diagnostic> {
+        self.parse_localized(power, orders, &Locale::english())
+    }
+
+    // parse orders written with the words of some locale (and any English
+    //   ones it doesn't replace)
+    // ex. "A Mün unterstützt A Kie - Ber" in German
+    pub fn parse_localized(&mut self, power: &Power, orders: String,
+            locale: &Locale) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+        for (idx, line) in orders.lines().enumerate() {
+            if line.trim().is_empty() { continue; }
+
+            let whole = whole_line(line);
+            let tokens = self.tokenize(line, locale);
+            let parsed = match self.phase {
+                Phase::SpringDiplomacy | Phase::FallDiplomacy =>
+                    self.parse_order(&tokens, locale),
+                Phase::SpringRetreats | Phase::FallRetreats =>
+                    self.parse_retreat(&tokens, locale),
+                Phase::Builds =>
+                    self.parse_adjust(&tokens, locale),
+                Phase::GameOver =>
+                    Err(ParseError { kind: ParseErrorKind::Rejected, span: whole.clone() })
+            }.map_err(|e| e.within(line));
+
+            // an order for the wrong type of unit is dealt with as the rules say
+            let mut warnings = vec![];
+            let wrong_type = parsed.as_ref().ok()
+                .filter(|order| self.wrong_unit_type(order))
+                .and_then(|_| Token::unit_type(&tokens))
+                .map(|(_, token)| token.error(ParseErrorKind::WrongUnitType));
+
+            let (order, error) = match (parsed, wrong_type) {
+                (Ok(order), Some(wrong_type)) if
+                        self.variant.rules.unit_types == UnitTypeCheck::Reject =>
+                    (Some(order), Some(wrong_type)),
+                (Ok(order), wrong_type) => {
+                    if self.variant.rules.unit_types == UnitTypeCheck::Warn {
+                        warnings.extend(wrong_type);
+                    }
+                    let accepted = match order.clone() {
+                        ParsedOrder::Order { province, action, .. } =>
+                            self.add_order(power.clone(), province, action),
+                        ParsedOrder::Retreat { province, action, .. } =>
+                            self.add_retreat(power.clone(), province, action),
+                        ParsedOrder::Adjust { province, action, .. } =>
+                            self.add_adjust(power.clone(), province, action),
+                        ParsedOrder::Waive => self.orderer(power).is_some()
+                    };
+                    (Some(order), if accepted { None } else {
+                        Some(ParseError { kind: ParseErrorKind::Rejected, span: whole })
+                    })
+                },
+                (Err(error), _) => (None, Some(error))
+            };
+            diagnostics.push(Diagnostic {
+                number: idx + 1,
+                line: line.to_string(),
+                order,
+                error,
+                warnings,
+                keywords: tokens.iter()
+                    .filter_map(|t| t.keyword.map(|k| (k, t.span.clone()))).collect()
+            });
         }
+        diagnostics
     }
 
     pub fn apply(&mut self) {
@@ -23,7 +87,8 @@ impl Stpsyr {
             Phase::SpringRetreats | Phase::FallRetreats =>
                 self.apply_retreats(),
             Phase::Builds =>
-                self.apply_adjusts()
+                self.apply_adjusts(),
+            Phase::GameOver => {}
         }
     }
 
@@ -31,146 +96,543 @@ impl Stpsyr {
         bincode::serialize(self, bincode::Infinite).unwrap()
     }
 
-    pub fn deserialize(encoded: &Vec<u8>) {
+    pub fn deserialize(encoded: &[u8]) -> Stpsyr {
         bincode::deserialize(encoded).unwrap()
     }
 
-    fn parse_orders(&mut self, power: &Power, orders: String) {
-        for line in orders.lines() {
-            let line = line.to_lowercase()
-                .replace('(', "/")
-                .replace(" /", "/");
-            let tokens: Vec<&str> = line
-                .split(|c: char| !(c.is_lowercase() || c == '/') )
-                .collect();
-            let mut tokens_iter = tokens.iter().filter(|&token|
-                    (token.len() >= 3 ||
-                     *token == "s" || *token == "c" || *token == "vc") &&
-                    *token != "army" && *token != "fleet" &&
-                    *token != "hold" && *token != "holds" &&
-                    *token != "stand" && *token != "stands" &&
-                    *token != "move" && *token != "moves" &&
-                    *token != "the" && *token != "coast" && *token != "via"
-                ).map(|&token| match token {
-                    "support" | "supports" => "s",
-                    "convoy" | "convoys" | "vc" => "c",
-                    _ => token
-                });
-
-            let province = if let Some(p) = tokens_iter.next() {
-                Province::from(p)
-            } else { continue };
-
-            match tokens_iter.next() {
-                None => {}, // hold
-                Some(token2) => { match token2 {
-                    "s" => {
-                        // support
-                        let a = tokens_iter.next().unwrap();
-                        if let Some(b) = tokens_iter.next() {
-                            // support move
-                            self.add_order(power.clone(), province,
-                            Action::SupportMove {
-                                from: Province::from(a), to: Province::from(b)
-                            });
-                        } else {
-                            // support hold
-                            self.add_order(power.clone(), province,
-                            Action::SupportHold {
-                                to: Province::from(a)
-                            });
-                        }
-                    },
-                    "c" => {
-                        // convoy
-                        let from = tokens_iter.next().unwrap();
-                        let to = tokens_iter.next().unwrap();
-                        self.add_order(power.clone(), province, Action::Convoy {
-                            from: Province::from(from), to: Province::from(to)
-                        });
-                    },
-                    _ => {
-                        // regular move
-                        let vc = tokens_iter.next().map_or(false, |token|
-                            token == "c");
-                        self.add_order(power.clone(), province, Action::Move {
-                            to: Province::from(token2), convoyed: vc
-                        });
-                    }
-                } }
+    // every name of each province other than its short name (in lowercase):
+    //   its full name and aliases, and the aliases the locale gives it
+    fn names(&self, region: &MapRegion, locale: &Locale) -> Vec<String> {
+        Some(&region.full_name).into_iter().chain(region.aliases.iter())
+            .map(|n| n.to_lowercase())
+            .chain(locale.aliases.iter().filter(|a| a.0 == region.province)
+                .map(|a| a.1.clone()))
+            .collect()
+    }
+
+    // replace every full name or alias of a province in an order with its
+    //   short name, so that the rest of the parser only has to deal with those
+    // each character of the result comes with the columns of the original line
+    //   it came from, so errors can point back at what the player wrote
+    // ex. "A St. Petersburg - Norway" => "a stp - nwy"
+    fn expand_names(&self, line: &str, locale: &Locale) -> Vec<(char, Range<usize>)> {
+        let mut chars = lowercase(line);
+
+        // try the longest names first, so that e.g. "north atlantic ocean"
+        //   isn't mistaken for "north atlantic" followed by "ocean"
+        let mut names: Vec<(Vec<char>, &str)> = self.map.iter().flat_map(|r|
+            self.names(r, locale).into_iter()
+                .map(move |n| (n, &r.province.name[..])))
+            .filter(|(n, key)| !n.is_empty() && n != key)
+            .map(|(n, key)| (n.chars().collect(), key)).collect();
+        names.sort_by_key(|n| cmp::Reverse(n.0.len()));
+
+        // only replace whole words
+        let boundary = |c: Option<&(char, Range<usize>)>|
+            c.is_none_or(|c| !c.0.is_alphanumeric());
+        for (name, key) in names {
+            let mut start = 0;
+            while start + name.len() <= chars.len() {
+                let end = start + name.len();
+                if chars[start..end].iter().map(|c| c.0).eq(name.iter().cloned()) &&
+                        boundary(start.checked_sub(1).and_then(|i| chars.get(i))) &&
+                        boundary(chars.get(end)) {
+                    let span = chars[start].1.start..chars[end - 1].1.end;
+                    chars.splice(start..end, key.chars().map(|c| (c, span.clone())));
+                    start += key.chars().count();
+                } else {
+                    start += 1;
+                }
             }
         }
+
+        chars
+    }
+
+    // split a line of orders into words (after expanding names), with coasts
+    //   attached to their province however they were written, and keywords
+    //   in the words the order grammars understand (see translate)
+    // ex. "F St. Petersburg (sc) - Bot" => ["fleet", "stp/sc", "bot"]
+    // (words the locale doesn't know are tried in English too)
+    fn tokenize(&self, line: &str, locale: &Locale) -> Vec<Token> {
+        let is_province = |word: &str|
+            self.get_region(&Location::from(word).province).is_some();
+        let tokens = split_words(self.expand_names(line, locale));
+        translate(translate(tokens, locale, is_province), &Locale::english(), is_province)
+    }
+
+    // check whether an order gives a unit type that isn't the type of the
+    //   unit there (orders that don't give one, or are for nothing, are fine)
+    fn wrong_unit_type(&self, order: &ParsedOrder) -> bool {
+        let unit = match *order {
+            ParsedOrder::Order { ref province, unit_type: Some(t), .. } =>
+                self.get_unit(province).map(|u| u.unit_type != t),
+            ParsedOrder::Retreat { ref province, unit_type: Some(t), .. } =>
+                self.dislodged.iter().find(|(p, _)| p == province)
+                    .map(|(_, u)| u.unit_type != t),
+            ParsedOrder::Adjust { ref province, unit_type: Some(t),
+                    action: AdjustAction::Disband } =>
+                self.get_unit(province).map(|u| u.unit_type != t),
+            _ => None
+        };
+        unit.unwrap_or(false)
+    }
+
+    // look up a province named in an order, keeping any coast given with it
+    fn location(&self, token: &Token, locale: &Locale) -> Result<Location, ParseError> {
+        let location = any_location(token)?;
+        self.match_province(&location.province.name, locale)
+            .map(|province| Location { province, ..location })
+            .map_err(|kind| token.error(kind))
+    }
+
+    // like find_province, but also take the start of exactly one province's
+    //   full name or aliases
+    // anything else is an error, which for a typo suggests the provinces with
+    //   the closest names (see edit_distance)
+    // ex. "bur", "burg" => bur; "nor" is ambiguous; "burgandy" suggests bur
+    fn match_province(&self, name: &str, locale: &Locale)
+            -> Result<Province, ParseErrorKind> {
+        if let Some(province) = self.find_province(name) {
+            return Ok(province);
+        }
+        let name = &name.trim().to_lowercase()[..];
+
+        let names = |r: &MapRegion| self.names(r, locale);
+        let with_names = |r: &MapRegion| (r.province.clone(), r.full_name.clone());
+
+        let matches: Vec<(Province, String)> = self.map.iter()
+            .filter(|r| names(r).iter().any(|n| n.starts_with(name)))
+            .map(with_names).collect();
+        match matches.len() {
+            0 => {},
+            1 => return Ok(matches[0].0.clone()),
+            _ => return Err(ParseErrorKind::AmbiguousProvince { matches })
+        }
+
+        // (only names that are off by about one letter in three count)
+        let limit = cmp::max(1, name.chars().count() / 3);
+        let mut suggestions: Vec<(usize, &MapRegion)> = self.map.iter()
+            .filter_map(|r| names(r).iter().chain(Some(&r.province.name))
+                .map(|n| edit_distance(name, n)).min()
+                .filter(|&d| d <= limit).map(|d| (d, r)))
+            .collect();
+        suggestions.sort_by_key(|s| s.0);
+        Err(ParseErrorKind::UnknownProvince {
+            suggestions: suggestions.into_iter().take(3).map(|s| with_names(s.1)).collect()
+        })
+    }
+
+    fn parse_order(&self, tokens: &[Token], locale: &Locale)
+            -> Result<ParsedOrder, ParseError> {
+        let (location, unit_type, action) =
+            order_grammar(tokens, |t| self.location(t, locale))?;
+        Ok(ParsedOrder::Order { province: location.province, unit_type, action })
+    }
+
+    fn parse_retreat(&self, tokens: &[Token], locale: &Locale)
+            -> Result<ParsedOrder, ParseError> {
+        let (location, unit_type, action) =
+            retreat_grammar(tokens, |t| self.location(t, locale))?;
+        Ok(ParsedOrder::Retreat { province: location.province, unit_type, action })
+    }
+
+    fn parse_adjust(&self, tokens: &[Token], locale: &Locale)
+            -> Result<ParsedOrder, ParseError> {
+        Ok(match adjust_grammar(tokens, |t| self.location(t, locale))? {
+            Some((location, unit_type, action)) =>
+                ParsedOrder::Adjust { province: location.province, unit_type, action },
+            None => ParsedOrder::Waive
+        })
     }
 
-    fn parse_retreats(&mut self, power: &Power, orders: String) {
-        for line in orders.lines() {
-            let line = line.to_lowercase()
-                .replace('(', "/")
-                .replace(" /", "/")
-                .replace("/ ", "/");
-            let tokens: Vec<&str> = line
-                .split(|c: char| !(c.is_lowercase() || c == '/') )
-                .collect();
-            let mut tokens_iter = tokens.iter().filter(|&token|
-                    token.len() >= 3 &&
-                    *token != "army" && *token != "fleet" &&
-                    *token != "move" && *token != "moves" &&
-                    *token != "retreat" && *token != "retreats" &&
-                    *token != "disband" && *token != "disbands" &&
-                    *token != "the" && *token != "coast"
-                );
-
-            let p1 = if let Some(p) = tokens_iter.next() {
-                Province::from(*p)
-            } else { continue };
-
-            if let Some(p2) = tokens_iter.next() {
-                if tokens_iter.next().is_some() { continue; }
-                self.add_retreat(power.clone(), p1,
-                        RetreatAction::Move { to: Province::from(*p2) });
-            } else {
-                self.add_retreat(power.clone(), p1, RetreatAction::Disband);
+}
+
+// the columns of a line without the whitespace around it, for errors that
+//   aren't about any one part of it
+fn whole_line(line: &str) -> Range<usize> {
+    let start = line.chars().take_while(|c| c.is_whitespace()).count();
+    start..line.trim_end().chars().count()
+}
+
+impl ParseError {
+    // an error about a whole line
+    pub(crate) fn whole(kind: ParseErrorKind, line: &str) -> ParseError {
+        ParseError { kind, span: whole_line(line) }
+    }
+
+    // point an error with nothing to point at (see Token::missing) at the
+    //   whole line
+    fn within(self, line: &str) -> ParseError {
+        if self.span.start == self.span.end {
+            ParseError { span: whole_line(line), ..self }
+        } else { self }
+    }
+}
+
+// how many letters have to be added, removed, changed or swapped with the
+//   next one to turn one word into another
+// ex. "burgandy" => "burgundy" is 1, "bru" => "bur" is 1
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // d[i][j] is the distance between the first i letters of a and the
+    //   first j letters of b
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() { row[0] = i; }
+    for (j, cell) in d[0].iter_mut().enumerate() { *cell = j; }
+    for i in 1..a.len() + 1 {
+        for j in 1..b.len() + 1 {
+            let change = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            d[i][j] = cmp::min(cmp::min(d[i - 1][j] + 1, d[i][j - 1] + 1),
+                d[i - 1][j - 1] + change);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = cmp::min(d[i][j], d[i - 2][j - 2] + 1);
             }
         }
     }
+    d[a.len()][b.len()]
+}
 
-    fn parse_adjusts(&mut self, power: &Power, orders: String) {
-        for line in orders.lines() {
-            let line = line.to_lowercase()
-                .replace('(', "/")
-                .replace(" /", "/")
-                .replace("/ ", "/");
-            let tokens: Vec<&str> = line
-                .split(|c: char| !(c.is_lowercase() || c == '/') )
-                .collect();
-            let mut tokens_iter = tokens.iter().filter(|&token|
-                    (token.len() >= 3 ||
-                     *token == "d" || *token == "a" || *token == "f") &&
-                    *token != "build" &&
-                    *token != "the" && *token != "coast"
-                ).map(|&token| match token {
-                    "destroy" => "d",
-                    "army" => "a",
-                    "fleet" => "f",
-                    _ => token
-                });
-
-            match tokens_iter.next() {
-                Some("d") => if let Some(p) = tokens_iter.next() {
-                    self.add_adjust(power.clone(), Province::from(p),
-                            AdjustAction::Disband);
-                },
-                Some("a") => if let Some(p) = tokens_iter.next() {
-                    self.add_adjust(power.clone(), Province::from(p),
-                            AdjustAction::Build { unit_type: UnitType::Army });
-                },
-                Some("f") => if let Some(p) = tokens_iter.next() {
-                    self.add_adjust(power.clone(), Province::from(p),
-                            AdjustAction::Build { unit_type: UnitType::Fleet });
-                },
-                _ => {} // invalid
+// a line in lowercase, with the column each character came from
+fn lowercase(line: &str) -> Vec<(char, Range<usize>)> {
+    line.chars().enumerate()
+        .flat_map(|(i, c)| c.to_lowercase().map(move |c| (c, i..i + 1)))
+        .collect()
+}
+
+// split a line of orders into words, with coasts attached to their province
+//   however they were written, and each dash a word of its own (which locales
+//   can make a keyword, like English does for moves)
+// ex. "A Mun-Boh" => ["a", "mun", "-", "boh"]
+fn split_words(mut chars: Vec<(char, Range<usize>)>) -> Vec<Token> {
+    for c in &mut chars {
+        if c.0 == '(' { c.0 = '/'; }
+    }
+    // (spaces next to a slash are dropped, so "stp / sc" is "stp/sc")
+    let slash = |c: Option<&(char, Range<usize>)>| c.is_some_and(|c| c.0 == '/');
+    let mut idx = 0;
+    while idx < chars.len() {
+        if chars[idx].0.is_whitespace() && (slash(chars.get(idx + 1)) ||
+                slash(idx.checked_sub(1).and_then(|i| chars.get(i)))) {
+            chars.remove(idx);
+        } else { idx += 1; }
+    }
+
+    let mut words = vec![];
+    let mut word = vec![];
+    for c in chars {
+        if c.0.is_lowercase() || c.0 == '/' {
+            word.push(c);
+            continue;
+        }
+        if !word.is_empty() { words.push(mem::take(&mut word)); }
+        if c.0 == '-' { words.push(vec![c]); }
+    }
+    if !word.is_empty() { words.push(word); }
+
+    words.into_iter()
+        .map(|word| Token {
+            text: word.iter().map(|c| c.0).collect(),
+            span: word[0].1.start..word[word.len() - 1].1.end,
+            keyword: None
+        }).collect()
+}
+
+// mark the keywords of a locale (putting them in the words of Keyword::word),
+//   and drop the words it ignores, leaving anything that `is_province` alone
+// ex. ["u", "armee", "mun"] in German => ["support", "army", "mun"]
+fn translate<F>(tokens: Vec<Token>, locale: &Locale, is_province: F) -> Vec<Token>
+        where F: Fn(&str) -> bool {
+    tokens.into_iter().filter_map(|token| {
+        if is_province(&token.text) { return Some(token); }
+        if locale.ignored.contains(&token.text) { return None; }
+        Some(match locale.keyword(&token.text) {
+            Some(keyword) => Token {
+                text: keyword.word().to_string(), keyword: Some(keyword), ..token
+            },
+            None => token
+        })
+    }).collect()
+}
+
+// the grammars of orders for each kind of phase, which give back the location
+//   of the unit being ordered, the unit type the order gave (if any), and
+//   what it's ordered to do
+// keywords have already been marked (see translate), and any other word under
+//   three letters long (like "to") is skipped
+// provinces are looked up with `location`, which Stpsyr::parse checks against
+//   the map and the FromStr impls below take as they are
+fn order_grammar<F>(tokens: &[Token], location: F)
+        -> Result<(Location, Option<UnitType>, Action), ParseError>
+        where F: Fn(&Token) -> Result<Location, ParseError> {
+    let province = |t: &Token| location(t).map(|l| l.province);
+    let unit_type = Token::unit_type(tokens).map(|(t, _)| t);
+    let move_word = tokens.iter().find(|t| t.keyword == Some(Keyword::Move));
+    // the unit type given for a supported unit, between the support keyword
+    //   and where the unit is (ex. "S A mun")
+    let all = tokens;
+    let supported = |support: &Token, unit: &Token| all.iter()
+        .filter(|t| t.span.start > support.span.start && t.span.end <= unit.span.start)
+        .filter_map(|t| match t.keyword {
+            Some(Keyword::Army) => Some(UnitType::Army),
+            Some(Keyword::Fleet) => Some(UnitType::Fleet),
+            _ => None
+        }).next_back();
+    let mut tokens = tokens.iter().filter(|token| match token.keyword {
+            Some(Keyword::Army) | Some(Keyword::Fleet) | Some(Keyword::Hold) |
+                Some(Keyword::Move) | Some(Keyword::Coast) | Some(Keyword::Via) => false,
+            Some(_) => true,
+            None => token.text.len() >= 3
+        }).cloned().peekable();
+
+    let first = location(&tokens.next().ok_or_else(Token::missing)?)?;
+
+    let action = match tokens.next() {
+        // (a move with nowhere to go, like "A par -", isn't a hold)
+        None => match move_word {
+            Some(token) => return Err(token.error(ParseErrorKind::Unparseable)),
+            None => Action::Hold
+        },
+        Some(token2) => match token2.keyword {
+            Some(Keyword::Support) => {
+                // support
+                let a = tokens.next().ok_or_else(||
+                    token2.error(ParseErrorKind::Unparseable))?;
+                if let Some(b) = tokens.next() {
+                    // support move
+                    Action::SupportMove {
+                        from: province(&a)?, to: province(&b)?,
+                        supported: supported(&token2, &a)
+                    }
+                } else {
+                    // support hold
+                    Action::SupportHold {
+                        to: province(&a)?, supported: supported(&token2, &a)
+                    }
+                }
+            },
+            Some(Keyword::Convoy) => {
+                // convoy
+                let (from, to) = match (tokens.next(), tokens.next()) {
+                    (Some(from), Some(to)) => (from, to),
+                    _ => return Err(token2.error(ParseErrorKind::Unparseable))
+                };
+                Action::Convoy {
+                    from: province(&from)?, to: province(&to)?
+                }
+            },
+            _ => {
+                // regular move
+                let to = location(&token2)?;
+                let convoyed = tokens.peek()
+                    .is_some_and(|t| t.keyword == Some(Keyword::Convoy));
+                if convoyed { tokens.next(); }
+                Action::Move { to, convoyed }
             }
         }
+    };
+
+    Token::rest(tokens)?;
+    Ok((first, unit_type, action))
+}
+
+// retreats can be told to disband anywhere in the line, and the words for
+//   retreating are optional
+// ex. "A mun - boh", "A mun R boh", "Disband A mun", "mun disbands"
+fn retreat_grammar<F>(tokens: &[Token], location: F)
+        -> Result<(Location, Option<UnitType>, RetreatAction), ParseError>
+        where F: Fn(&Token) -> Result<Location, ParseError> {
+    let unit_type = Token::unit_type(tokens).map(|(t, _)| t);
+    let (mut disband, mut retreat) = (None, None);
+    let mut places = vec![];
+    for token in tokens {
+        match token.keyword {
+            Some(Keyword::Disband) | Some(Keyword::Destroy) => disband = Some(token),
+            Some(Keyword::Move) | Some(Keyword::Retreat) =>
+                retreat = retreat.or(Some(token)),
+            Some(Keyword::Army) | Some(Keyword::Fleet) | Some(Keyword::Coast) => {},
+            Some(_) => return Err(token.error(ParseErrorKind::Unparseable)),
+            None => if token.text.len() >= 3 { places.push(token); }
+        }
+    }
+
+    let mut places = places.into_iter();
+    let first = location(places.next().ok_or_else(Token::missing)?)?;
+    let action = match (places.next(), disband) {
+        (Some(to), None) => RetreatAction::Move { to: location(to)? },
+        (Some(_), Some(disband)) => return Err(disband.error(ParseErrorKind::Unparseable)),
+        // (a retreat with nowhere to go, like "A mun -", isn't a disband)
+        (None, None) => match retreat {
+            Some(token) => return Err(token.error(ParseErrorKind::Unparseable)),
+            None => RetreatAction::Disband
+        },
+        (None, Some(_)) => RetreatAction::Disband
+    };
+
+    Token::rest(places.cloned())?;
+    Ok((first, unit_type, action))
+}
+
+// builds and removals can have their keyword anywhere in the line (or none at
+//   all, for a build), and a waived build gives back None
+// ex. "Build F kie", "F kie", "Remove A mun", "A mun disbands", "Waive"
+fn adjust_grammar<F>(tokens: &[Token], location: F)
+        -> Result<Option<(Location, Option<UnitType>, AdjustAction)>, ParseError>
+        where F: Fn(&Token) -> Result<Location, ParseError> {
+    let unit_type = Token::unit_type(tokens).map(|(t, _)| t);
+    let (mut build, mut remove, mut waive) = (None, None, None);
+    let mut places = vec![];
+    for token in tokens {
+        match token.keyword {
+            Some(Keyword::Build) => build = Some(token),
+            Some(Keyword::Disband) | Some(Keyword::Destroy) => remove = Some(token),
+            Some(Keyword::Waive) => waive = Some(token),
+            Some(Keyword::Army) | Some(Keyword::Fleet) | Some(Keyword::Coast) => {},
+            Some(_) => return Err(token.error(ParseErrorKind::Unparseable)),
+            None => if token.text.len() >= 3 { places.push(token); }
+        }
+    }
+
+    let mut places = places.into_iter();
+    if waive.is_some() {
+        // (a waive can say it's of a build, but nothing else)
+        return match remove.or(places.next()) {
+            Some(token) => Err(token.error(ParseErrorKind::Unparseable)),
+            None => Ok(None)
+        };
+    }
+
+    let first = places.next().ok_or_else(Token::missing)?;
+    let location = location(first)?;
+    let action = match (remove, build, unit_type) {
+        (Some(remove), Some(_), _) => return Err(remove.error(ParseErrorKind::Unparseable)),
+        (Some(_), None, _) => AdjustAction::Disband,
+        (None, _, Some(UnitType::Army)) =>
+            AdjustAction::Build { unit_type: UnitType::Army, coast: None },
+        (None, _, Some(UnitType::Fleet)) =>
+            AdjustAction::Build { unit_type: UnitType::Fleet, coast: location.coast },
+        (None, _, None) => return Err(first.error(ParseErrorKind::Unparseable))
+    };
+
+    Token::rest(places.cloned())?;
+    Ok(Some((location, unit_type, action)))
+}
+
+// a Token is one word of a line of orders, the columns of the original line
+//   it came from, and the keyword it is, if it's one (see translate)
+#[derive(Clone)]
+struct Token {
+    text: String,
+    span: Range<usize>,
+    keyword: Option<Keyword>
+}
+
+impl Token {
+
+    // the unit type a line gives for the unit it's about, if any, and where
+    //   it is (the first one before anything that isn't a keyword)
+    // ex. "F par-bur" and "Build F kie" give a fleet, "Par S A mar" gives none
+    fn unit_type(tokens: &[Token]) -> Option<(UnitType, &Token)> {
+        tokens.iter().take_while(|t| t.keyword.is_some()).find_map(|t| match t.keyword {
+            Some(Keyword::Army) => Some((UnitType::Army, t)),
+            Some(Keyword::Fleet) => Some((UnitType::Fleet, t)),
+            _ => None
+        })
+    }
+
+    // an error about this token
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError { kind, span: self.span.clone() }
+    }
+
+    // an error about something missing from the line, with nothing in it to
+    //   point at (see ParseError::within)
+    fn missing() -> ParseError {
+        ParseError { kind: ParseErrorKind::Unparseable, span: 0..0 }
+    }
+
+    // make sure nothing's left over at the end of an order
+    fn rest<I: Iterator<Item=Token>>(tokens: I) -> Result<(), ParseError> {
+        let rest: Vec<Token> = tokens.collect();
+        match (rest.first(), rest.last()) {
+            (Some(first), Some(last)) => Err(ParseError {
+                kind: ParseErrorKind::Unparseable,
+                span: first.span.start..last.span.end
+            }),
+            _ => Ok(())
+        }
     }
 
 }
+
+// orders, retreats and adjusts can be read back from how they're displayed
+//   (see the Display impls in types.rs), with or without the owner in front
+// the provinces aren't checked against any map, and without an owner, it's
+//   left blank for whoever reads them to fill in
+// ex. "England: F Nth C A Lon - Nwy".parse::<Order>()
+
+// split an order into its owner, if it has one, and the words after that
+fn owned_words(s: &str) -> (Power, Vec<Token>) {
+    let (owner, skip) = match s.find(':') {
+        Some(idx) => (Power::from(s[..idx].trim()), s[..idx + 1].chars().count()),
+        None => (Power::from(""), 0)
+    };
+    (owner, translate(split_words(lowercase(s).into_iter()
+        .filter(|c| c.1.start >= skip).collect()), &Locale::english(), |_| false))
+}
+
+// take a province as it's given, coast and all (a coast that's started but
+//   never named, like "par()", is an error)
+fn any_location(token: &Token) -> Result<Location, ParseError> {
+    if token.text.ends_with('/') {
+        return Err(token.error(ParseErrorKind::Unparseable));
+    }
+    Ok(Location::from(&token.text[..]))
+}
+
+impl FromStr for Order {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Order, ParseError> {
+        let (owner, tokens) = owned_words(s);
+        let (location, unit_type, action) = order_grammar(&tokens, any_location)
+            .map_err(|e| e.within(s))?;
+        Ok(Order {
+            owner,
+            province: location.province,
+            unit_type: unit_type.ok_or_else(|| Token::missing().within(s))?,
+            coast: location.coast,
+            action,
+            resolution: false,
+            state: OrderState::UNRESOLVED,
+            id: 0
+        })
+    }
+}
+
+impl FromStr for Retreat {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Retreat, ParseError> {
+        let (owner, tokens) = owned_words(s);
+        let (location, unit_type, action) = retreat_grammar(&tokens, any_location)
+            .map_err(|e| e.within(s))?;
+        Ok(Retreat {
+            owner,
+            province: location.province,
+            unit_type: unit_type.ok_or_else(|| Token::missing().within(s))?,
+            coast: location.coast,
+            action
+        })
+    }
+}
+
+impl FromStr for Adjust {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Adjust, ParseError> {
+        let (owner, tokens) = owned_words(s);
+        let (location, _, action) = adjust_grammar(&tokens, any_location)
+            .map_err(|e| e.within(s))?
+            .ok_or_else(|| ParseError::whole(ParseErrorKind::Unparseable, s))?;
+        Ok(Adjust { owner, province: location.province, action })
+    }
+}
@@ -4,8 +4,11 @@ use stpsyr::types::*;
 
 impl Stpsyr {
 
-    // the publicly exposed function to modify self.retreats
-    pub fn add_retreat(&mut self, owner: Power, province: Province, action: RetreatAction) {
+    // the publicly exposed function to modify self.retreats, returning whether the
+    //   retreat was accepted (in which case it replaces any retreat the unit
+    //   already had)
+    pub fn add_retreat(&mut self, owner: Power, province: Province, action: RetreatAction)
+            -> bool {
         // TODO refactor this method to get rid of repetition from verification
         //   used in add_order
         // TODO can't retreat to the place that attacked you
@@ -15,36 +18,55 @@ impl Stpsyr {
             _ => panic!("add_retreat called during non-retreat phase")
         };
 
+        // eliminated powers (and powers in civil disorder) can't give orders
+        let owner = if let Some(owner) = self.orderer(&owner) { owner }
+            else { return false; };
+
         // there has to be a unit that was dislodged here to order it
         let unit = if let Some(unit) = self.dislodged.iter().find(|&&(ref p, _)|
                 p == &province).map(|&(_, ref u)| u.clone()) { unit }
-            else { return; };
+            else { return false; };
 
         // can't order a unit that's not yours
-        if unit.owner != owner { return; }
-
-        // can't order to a province you can't reach or a province that was
-        //   contested during the last diplomacy phase
-        if match action {
-            RetreatAction::Move { ref to } => {
-                let r = self.get_region(&province).unwrap();
-                self.contested.contains(to) || !match unit.unit_type {
-                    UnitType::Army => r.army_borders.clone(),
-                    UnitType::Fleet => r.fleet_borders.clone().into_iter()
-                        .filter(|p|
-                            p.from_coast == r.province.coast &&
-                            p.coast == to.coast)
-                        .collect()
-                }.contains(to)
-            },
-            _ => false
-        } { return; }
+        if unit.owner != owner { return false; }
+
+        // can't order to a province that isn't one of the unit's retreat options
+        //   (and any coast given for a province that doesn't need one is
+        //   dropped)
+        let action = if let RetreatAction::Move { to } = action {
+            if let Some(to) = self.retreat_options(&province).into_iter()
+                    .find(|l| l.province == to.province &&
+                        (l.coast.is_none() || l.coast == to.coast)) {
+                RetreatAction::Move { to }
+            } else { return false; }
+        } else { action };
 
+        self.retreats.retain(|r| r.province != province);
         self.retreats.push(Retreat {
             owner: owner,
             province: province,
+            unit_type: unit.unit_type,
+            coast: unit.coast,
             action: action
         });
+        true
+    }
+
+    // get every province a dislodged unit could retreat to: anywhere it could
+    //   move to that's empty and wasn't contested during the last diplomacy
+    //   phase
+    pub fn retreat_options(&self, province: &Province) -> Vec<Location> {
+        let unit = if let Some((_, unit)) = self.dislodged.iter()
+                .find(|(p, _)| p == province) { unit } else { return vec![]; };
+        let r = self.get_region(province).unwrap();
+        match unit.unit_type {
+            UnitType::Army => r.army_borders.iter().cloned().map(Location::from)
+                .collect(),
+            UnitType::Fleet => r.fleet_moves(unit.coast)
+        }.into_iter().filter(|l|
+            !self.contested.contains(&l.province) &&
+            self.get_unit(&l.province).is_none() && self.is_passable(&l.province)
+        ).collect()
     }
 
     // the publicly exposed function that is called once all retreats have been
@@ -60,6 +82,7 @@ impl Stpsyr {
 
             for retreat in &self.retreats {
                 if let RetreatAction::Move { ref to } = retreat.action {
+                    let to = &to.province;
                     if attempts.contains(to) { conflicts.insert(to); }
                     else { attempts.insert(to); }
                 }
@@ -68,14 +91,16 @@ impl Stpsyr {
             for retreat in &self.retreats {
                 match retreat.action {
                     RetreatAction::Move { ref to } => {
-                        if !conflicts.contains(to) {
+                        if !conflicts.contains(&to.province) {
                             // process the retreat
                             let from_idx = self.dislodged.iter()
                                 .position(|&(ref p, _)| *p == retreat.province).unwrap();
                             let to_idx = self.map.iter()
-                                .position(|r| r.province == *to).unwrap();
+                                .position(|r| r.province == to.province).unwrap();
                             assert!(self.map[to_idx].unit.is_none());
-                            self.map[to_idx].unit = Some(self.dislodged[from_idx].1.clone());
+                            self.map[to_idx].unit = Some(Unit {
+                                coast: to.coast, ..self.dislodged[from_idx].1.clone()
+                            });
                         }
                     },
                     // handle disbands as if they were NMRs - no difference anyway
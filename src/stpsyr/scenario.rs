@@ -0,0 +1,104 @@
+use stpsyr::types::*;
+
+// a Scenario is used to set up an arbitrary position directly, instead of
+//   playing orders from the starting position until we get there
+// ex. s.scenario().clear().unit(&Location::from("lon"), &england, UnitType::Fleet)
+pub struct Scenario<'a> {
+    stpsyr: &'a mut Stpsyr
+}
+
+impl Stpsyr {
+
+    // start setting up a position on this board
+    pub fn scenario(&mut self) -> Scenario<'_> {
+        Scenario { stpsyr: self }
+    }
+
+}
+
+impl<'a> Scenario<'a> {
+
+    // remove every unit and SC owner from the board, and throw away anything
+    //   that was going on in the current phase
+    pub fn clear(&mut self) -> &mut Scenario<'a> {
+        for r in &mut self.stpsyr.map {
+            r.owner = None;
+            r.unit = None;
+        }
+        for (_, status) in &mut self.stpsyr.powers {
+            *status = PowerStatus::Active;
+        }
+        self.stpsyr.orders = vec![];
+        self.stpsyr.resolved = vec![];
+        self.stpsyr.retreats = vec![];
+        self.stpsyr.adjusts = vec![];
+        self.stpsyr.dislodged = vec![];
+        self.stpsyr.contested.clear();
+        self.stpsyr.proposals = vec![];
+        self.stpsyr.result = None;
+        self
+    }
+
+    // place a unit, replacing whatever was there before
+    // for fleets on multi-coast provinces, the coast of the given location is
+    //   the one the fleet ends up on
+    pub fn unit(&mut self, location: &Location, owner: &Power,
+            unit_type: UnitType) -> &mut Scenario<'a> {
+        let region = self.region(&location.province, unit_type);
+        region.unit = Some(Unit {
+            owner: owner.clone(), unit_type, coast: location.coast
+        });
+        self
+    }
+
+    // take a unit off the board
+    pub fn remove_unit(&mut self, province: &Province) -> &mut Scenario<'a> {
+        self.stpsyr.map.iter_mut().find(|r| r.province == *province)
+            .expect("no such province").unit = None;
+        self
+    }
+
+    // set (or clear, with None) the owner of a province
+    pub fn owner(&mut self, province: &Province, owner: Option<&Power>)
+            -> &mut Scenario<'a> {
+        self.stpsyr.map.iter_mut().find(|r| r.province == *province)
+            .expect("no such province").owner = owner.cloned();
+        self
+    }
+
+    // add a dislodged unit that will have to retreat (this only makes sense
+    //   in a retreat phase)
+    pub fn dislodged(&mut self, location: &Location, owner: &Power,
+            unit_type: UnitType) -> &mut Scenario<'a> {
+        let province = &location.province;
+        self.region(province, unit_type);
+        self.stpsyr.dislodged.retain(|(p, _)| p != province);
+        self.stpsyr.dislodged.push((province.clone(),
+            Unit { owner: owner.clone(), unit_type, coast: location.coast }));
+        self
+    }
+
+    // mark a province as contested, so that nothing can retreat there
+    pub fn contested(&mut self, province: &Province) -> &mut Scenario<'a> {
+        self.stpsyr.contested.insert(province.clone());
+        self
+    }
+
+    // choose the phase and year the game is in
+    pub fn phase(&mut self, phase: Phase, year: i32) -> &mut Scenario<'a> {
+        self.stpsyr.phase = phase;
+        self.stpsyr.year = year;
+        self
+    }
+
+    // find the region a unit is being put in, making sure it can be there
+    fn region(&mut self, province: &Province, unit_type: UnitType) -> &mut MapRegion {
+        let region = self.stpsyr.map.iter_mut().find(|r| r.province == *province)
+            .expect("no such province");
+        if !region.kind.allows(unit_type) {
+            panic!("{:?} can't be placed in {:?}", unit_type, province);
+        }
+        region
+    }
+
+}
@@ -0,0 +1,67 @@
+use stpsyr::types::*;
+
+impl Stpsyr {
+
+    // replace every order a power has given this phase with a new set (see
+    //   parse, which adds to them instead), so that a player can resubmit
+    //   their orders as often as they like until the phase is adjudicated
+    pub fn submit(&mut self, power: &Power, orders: String) -> Vec<Diagnostic> {
+        self.withdraw_all(power);
+        self.parse(power, orders)
+    }
+
+    // take back the order a power gave to the unit in a province this phase
+    //   (an order, retreat or adjust, depending on the phase), returning
+    //   whether there was one
+    // a unit without an order holds (or disbands, if it was dislodged)
+    pub fn withdraw(&mut self, power: &Power, province: &Province) -> bool {
+        let mine = |owner: &Power, p: &Province| owner == power && p == province;
+        let before = self.orders.len() + self.retreats.len() + self.adjusts.len();
+        self.orders.retain(|o| !mine(&o.owner, &o.province));
+        self.retreats.retain(|r| !mine(&r.owner, &r.province));
+        self.adjusts.retain(|a| !mine(&a.owner, &a.province));
+        self.renumber_orders();
+        before != self.orders.len() + self.retreats.len() + self.adjusts.len()
+    }
+
+    // take back every order a power gave this phase
+    pub fn withdraw_all(&mut self, power: &Power) {
+        self.orders.retain(|o| o.owner != *power);
+        self.retreats.retain(|r| r.owner != *power);
+        self.adjusts.retain(|a| a.owner != *power);
+        self.renumber_orders();
+    }
+
+    // get the orders of every one of a power's units during a diplomacy
+    //   phase, in the order of the map, including the ones it hasn't ordered
+    //   yet (which hold)
+    pub fn orders_for(&self, power: &Power) -> Vec<UnitOrder> {
+        self.map.iter().filter_map(|r| r.unit.as_ref()
+                .filter(|u| u.owner == *power).map(|u| (r, u)))
+            .map(|(r, unit)| match self.orders.iter()
+                    .find(|o| o.province == r.province) {
+                Some(order) => UnitOrder { order: order.clone(), given: true },
+                None => UnitOrder {
+                    order: Order {
+                        owner: unit.owner.clone(),
+                        province: r.province.clone(),
+                        unit_type: unit.unit_type,
+                        coast: unit.coast,
+                        action: Action::Hold,
+                        resolution: false,
+                        state: OrderState::UNRESOLVED,
+                        id: 0
+                    },
+                    given: false
+                }
+            }).collect()
+    }
+
+    // orders are resolved by their ids, which are their places in self.orders
+    fn renumber_orders(&mut self) {
+        for (id, order) in self.orders.iter_mut().enumerate() {
+            order.id = id;
+        }
+    }
+
+}
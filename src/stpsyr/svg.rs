@@ -14,12 +14,13 @@ enum LookState {
 impl Stpsyr {
 
     pub fn render_svg(&self, path: String) -> io::Result<()> {
-        let in_file = BufReader::new(File::open("data/standard.svg")?);
+        let in_file = BufReader::new(File::open(&self.variant.svg)?);
         let mut out_file = File::create(path)?;
         let mut look_state = LookState::Nothing;
         let mut style_line = String::new();
         let mut data_line = String::new();
         let mut province_centers = HashMap::new();
+        let mut title = None;
 
         for line in in_file.lines() {
             let mut line = line?;
@@ -35,14 +36,12 @@ impl Stpsyr {
 
                     let color = if let Some(p) = self.map.iter()
                             .find(|&r| r.province == province) {
-                        if p.army_borders.is_empty() { "c5dfea" }
-                        else if p.owner == Some(Power::from("Russia"))  { "a87e9f" }
-                        else if p.owner == Some(Power::from("Austria")) { "c48f85" }
-                        else if p.owner == Some(Power::from("Turkey"))  { "eaeaaf" }
-                        else if p.owner == Some(Power::from("Italy"))   { "a4c499" }
-                        else if p.owner == Some(Power::from("France"))  { "79afc6" }
-                        else if p.owner == Some(Power::from("Germany")) { "a08a75" }
-                        else if p.owner == Some(Power::from("England")) { "efc4e4" }
+                        if p.kind == ProvinceKind::Sea { "c5dfea" }
+                        else if p.kind == ProvinceKind::Impassable { "8c8c8c" }
+                        else if let Some(info) = p.owner.as_ref()
+                                .and_then(|o| self.variant.power_info(o)) {
+                            &info.color[..]
+                        }
                         else { "e2c69e" }
                     } else { "000000" };
 
@@ -81,9 +80,19 @@ impl Stpsyr {
                     writeln!(out_file, "{}", data_line)?;
 
                     // find the "visual" center of the polygon
+                    title = Some(self.full_name(&province));
                     province_centers.insert(province, poly_center(&points));
                 },
 
+                // give each province a title with its full name, which shows up
+                //   when hovering over it
+                LookState::Provinces if title.is_some() &&
+                        line.trim_end().ends_with("/>") => {
+                    let len = line.trim_end().len() - 2;
+                    line = format!("{}><title>{}</title></path>", &line[..len],
+                        title.take().unwrap());
+                },
+
                 LookState::Provinces if line.trim().starts_with("d=\"") => {
                     data_line = line.clone();
                     line = String::new();
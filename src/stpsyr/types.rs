@@ -1,15 +1,18 @@
 use std::fmt;
 use std::cmp;
 use std::hash;
+use std::ops::Range;
 
 use std::collections::HashSet;
 
-// the only information attached to a Unit is its owner and type
+// the only information attached to a Unit is its owner and type, and for
+//   fleets in multi-coast provinces, which coast it's on
 // ex. "Austrian fleet"
 #[derive(Serialize,Deserialize,Clone)]
 pub struct Unit {
     pub owner: Power,
-    pub unit_type: UnitType
+    pub unit_type: UnitType,
+    pub coast: Option<char>
 }
 impl fmt::Debug for Unit {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -18,53 +21,114 @@ impl fmt::Debug for Unit {
 }
 #[derive(Serialize,Deserialize,Clone,Copy,Debug,PartialEq)]
 pub enum UnitType { Army, Fleet }
+impl fmt::Display for UnitType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match *self { UnitType::Army => "A", UnitType::Fleet => "F" })
+    }
+}
 
-// a Province is an extension of a String, partially for semantics, but also
-//   because we need to take coasts into account when enumerating borders
-#[derive(Serialize,Deserialize,Clone,Eq)]
+// a Province is an extension of a String for semantics; it names a whole
+//   province, coasts and all (any coast given when converting from a string
+//   is dropped, see Location for that)
+// ex. spa
+#[derive(Serialize,Deserialize,Clone,PartialEq,Eq,Hash)]
 pub struct Province {
-    pub name: String,
-    pub coast: Option<char>,
-    pub from_coast: Option<char>
+    pub name: String
 }
 impl fmt::Debug for Province {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}{}{}", self.name,
-            self.coast.map_or(String::new(), |coast| format!("/{}c", coast)),
-            self.from_coast.map_or(String::new(), |coast| format!(" [from {}c]", coast)))
+        write!(f, "{}", self.name)
+    }
+}
+// (in standard notation, names are capitalized: "Par", except for the ones
+//   in NOTATION)
+const NOTATION: &[(&str, &str)] = &[("stp", "StP")];
+impl fmt::Display for Province {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(&(_, name)) = NOTATION.iter().find(|n| n.0 == self.name) {
+            return write!(f, "{}", name);
+        }
+        let mut chars = self.name.chars();
+        match chars.next() {
+            Some(c) => write!(f, "{}{}", c.to_uppercase(), chars.as_str()),
+            None => Ok(())
+        }
     }
 }
 impl From<String> for Province {
     fn from(s: String) -> Province {
+        Location::from(s).province
+    }
+}
+impl<'a> From<&'a str> for Province {
+    fn from(s: &str) -> Province {
+        Province::from(s.to_string())
+    }
+}
+
+// a Location is a place a unit can be: a province, and for multi-coast
+//   provinces, optionally one of its coasts
+// unlike provinces, locations on different coasts are never equal
+// ex. spa/nc, spa
+#[derive(Serialize,Deserialize,Clone,PartialEq,Eq,Hash)]
+pub struct Location {
+    pub province: Province,
+    pub coast: Option<char>
+}
+impl fmt::Debug for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}{}", self.province,
+            self.coast.map_or(String::new(), |coast| format!("/{}c", coast)))
+    }
+}
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.province,
+            self.coast.map_or(String::new(), |coast| format!("/{}c", coast)))
+    }
+}
+impl From<String> for Location {
+    fn from(s: String) -> Location {
         if let Some(idx) = s.find('/') {
             let mut s = s;
             let coast = s.chars().nth(idx + 1);
             s.truncate(idx);
-            Province { name: s, coast: coast, from_coast: None }
+            Location { province: Province { name: s }, coast }
         } else {
-            Province { name: s, coast: None, from_coast: None }
+            Location { province: Province { name: s }, coast: None }
         }
     }
 }
-impl<'a> From<&'a str> for Province {
-    fn from(s: &str) -> Province {
-        Province::from(s.to_string())
+impl<'a> From<&'a str> for Location {
+    fn from(s: &str) -> Location {
+        Location::from(s.to_string())
     }
 }
-impl cmp::PartialEq for Province {
-    fn eq(&self, other: &Province) -> bool {
-        self.name == other.name
+impl From<Province> for Location {
+    fn from(province: Province) -> Location {
+        Location { province, coast: None }
     }
 }
-impl hash::Hash for Province {
-    fn hash<H: hash::Hasher>(&self, state: &mut H) {
-        self.name.hash(state);
+
+// a Border is one entry in a list of fleet borders: the location it leads
+//   to, and for multi-coast provinces, which coast it leaves from
+#[derive(Serialize,Deserialize,Clone,PartialEq)]
+pub struct Border {
+    pub from_coast: Option<char>,
+    pub to: Location
+}
+impl fmt::Debug for Border {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}{}", self.to,
+            self.from_coast.map_or(String::new(), |coast| format!(" [from {}c]", coast)))
     }
 }
 
 // a Power is simply a wrapper around a String for semantics
+// powers are compared (and hashed) ignoring case, so "italy" and "Italy" are
+//   the same power; Variant::find_power gets the name the variant uses
 // ex. Germany, Austria
-#[derive(Serialize,Deserialize,Clone,Eq,Hash)]
+#[derive(Serialize,Deserialize,Clone,Eq)]
 pub struct Power {
     pub name: String
 }
@@ -88,18 +152,84 @@ impl cmp::PartialEq for Power {
         self.name.to_lowercase() == other.name.to_lowercase()
     }
 }
+impl hash::Hash for Power {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.name.to_lowercase().hash(state);
+    }
+}
+impl Power {
+    // the owner of neutral garrisons, which never move, can't be ordered or
+    //   supported, and are removed instead of retreating when dislodged
+    // its name is reserved, so no variant can have a power called that
+    pub fn neutral() -> Power {
+        Power::from("(neutral)")
+    }
+    pub fn is_neutral(&self) -> bool {
+        *self == Power::neutral()
+    }
+}
+
+// a PowerStatus records whether a power is still taking part in the game
+// eliminated powers remember the year they lost their last unit and SC, and
+//   powers in civil disorder are still on the board but give no orders
+#[derive(Serialize,Deserialize,Clone,Copy,Debug,PartialEq)]
+pub enum PowerStatus {
+    Active,
+    Eliminated { year: i32 },
+    CivilDisorder
+}
+
+// a Standing is one row of a standings table, as returned by standings()
+#[derive(Clone,Debug)]
+pub struct Standing {
+    pub power: Power,
+    pub status: PowerStatus,
+    pub centers: u32,
+    pub units: u32
+}
+
+// what sort of place a province is: armies can be in Land and Coastal
+//   provinces, fleets can be in Sea and Coastal ones, and nothing can ever go
+//   into an Impassable one
+// an Island is a coastal province with no land borders, which armies can only
+//   get to or leave by convoy
+#[derive(Serialize,Deserialize,Clone,Copy,Debug,PartialEq)]
+pub enum ProvinceKind { Land, Sea, Coastal, Island, Impassable }
+impl ProvinceKind {
+    // whether a unit of this type can be in a province of this kind
+    pub fn allows(self, unit_type: UnitType) -> bool {
+        matches!((self, unit_type),
+            (ProvinceKind::Coastal, _) | (ProvinceKind::Island, _) |
+            (ProvinceKind::Land, UnitType::Army) |
+            (ProvinceKind::Sea, UnitType::Fleet))
+    }
+}
 
 // a MapRegion is a location on the map, storing the province, whether it's an
 //   SC, its current owner, the unit in it (not necessarily with the same owner
 //   as the region), and its borders (stored separately for fleets and armies)
+// it also has a full name for display, and any other names players might use
+//   for it in orders (both of these are in addition to the short name)
+// canals are coastal provinces that fleets can sail through from any of the
+//   seas around them, so they never have separate coasts
+// fleets can only convoy in provinces marked as convoy-capable (usually every
+//   sea, but some variants allow it on some coasts or forbid it in some seas)
+// conquered_by is only used with BuildRule::Conquest
 #[derive(Serialize,Deserialize,Clone)]
 pub struct MapRegion {
     pub province: Province,
+    pub full_name: String,
+    pub aliases: Vec<String>,
+    pub kind: ProvinceKind,
+    pub canal: bool,
+    pub convoy: bool,
+    // powers that have made this an extra home center by conquest
+    pub conquered_by: Vec<Power>,
     pub sc: bool,
     pub owner: Option<Power>,
     pub home_power: Option<Power>,
     pub unit: Option<Unit>,
-    pub fleet_borders: Vec<Province>,
+    pub fleet_borders: Vec<Border>,
     pub army_borders: Vec<Province>
 }
 impl fmt::Debug for MapRegion {
@@ -116,64 +246,376 @@ impl cmp::PartialEq for MapRegion {
         self.province == other.province
     }
 }
+impl MapRegion {
+    // the coasts of a multi-coast province, in the order the map gives them
+    //   (empty for everything else)
+    pub fn coasts(&self) -> Vec<char> {
+        let mut coasts = vec![];
+        for coast in self.fleet_borders.iter().filter_map(|b| b.from_coast) {
+            if !coasts.contains(&coast) { coasts.push(coast); }
+        }
+        coasts
+    }
+
+    // everywhere a fleet on the given coast of this province can move to
+    pub fn fleet_moves(&self, coast: Option<char>) -> Vec<Location> {
+        self.fleet_borders.iter().filter(|b| b.from_coast == coast)
+            .map(|b| b.to.clone()).collect()
+    }
+
+    // where the unit in this province is
+    pub fn unit_location(&self) -> Option<Location> {
+        self.unit.as_ref().map(|u| Location {
+            province: self.province.clone(), coast: u.coast
+        })
+    }
+}
+
+// a MapProblem is something wrong with a map, as found by validate_map()
+#[derive(Clone,Debug,PartialEq)]
+pub enum MapProblem {
+    // a province that was added twice (only found by MapBuilder)
+    DuplicateProvince { province: Province },
+    // something was said about a province that was never added (only found
+    //   by MapBuilder)
+    UnknownProvince { province: Province },
+    // a border to a province (or coast) that isn't on the map
+    DanglingBorder { from: Location, to: Location, unit_type: UnitType },
+    // a border that doesn't have a matching border going the other way
+    AsymmetricBorder { from: Location, to: Location, unit_type: UnitType },
+    // a fleet border to a multi-coast province that doesn't say which coast
+    MissingCoast { from: Location, to: Location },
+    // a starting unit in a province it couldn't move in or out of
+    BadStartingUnit { province: Province, unit_type: UnitType },
+    // a province whose kind (or canal or convoy flag) doesn't match its borders
+    WrongKind { province: Province, kind: ProvinceKind },
+    // a starting unit that isn't on one of its owner's home centers
+    StartingUnitOffCenter { province: Province, owner: Power },
+    // a home province that isn't an SC
+    HomeNotCenter { province: Province, power: Power }
+}
+impl fmt::Display for MapProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MapProblem::DuplicateProvince { ref province } =>
+                write!(f, "{:?} is added more than once", province),
+            MapProblem::UnknownProvince { ref province } =>
+                write!(f, "{:?} doesn't exist", province),
+            MapProblem::DanglingBorder { ref from, ref to, unit_type } =>
+                write!(f, "{:?} has a {:?} border to {:?}, which doesn't exist",
+                    from, unit_type, to),
+            MapProblem::AsymmetricBorder { ref from, ref to, unit_type } =>
+                write!(f, "{:?} has a {:?} border to {:?}, but not the other way",
+                    from, unit_type, to),
+            MapProblem::MissingCoast { ref from, ref to } =>
+                write!(f, "{:?} has a Fleet border to {:?} without a coast",
+                    from, to),
+            MapProblem::BadStartingUnit { ref province, unit_type } =>
+                write!(f, "{:?} starts with a {:?}, which can't be there",
+                    province, unit_type),
+            MapProblem::WrongKind { ref province, kind } =>
+                write!(f, "{:?} is {:?}, which doesn't match its borders",
+                    province, kind),
+            MapProblem::StartingUnitOffCenter { ref province, ref owner } =>
+                write!(f, "{:?} starts with a unit, but isn't a home center of {:?}",
+                    province, owner),
+            MapProblem::HomeNotCenter { ref province, ref power } =>
+                write!(f, "{:?} is a home of {:?}, but isn't an SC",
+                    province, power)
+        }
+    }
+}
 
 // here are some utility types for the Order struct
 #[derive(Serialize,Deserialize,Clone,Debug,PartialEq)]
 pub enum OrderState { UNRESOLVED, GUESSING, RESOLVED }
-#[derive(Serialize,Deserialize,Clone,Debug)]
+// supports keep the type of the unit they support, if it was given (orders
+//   that are accepted always have it, see add_order); convoys are always of
+//   armies
+#[derive(Serialize,Deserialize,Clone,Debug,PartialEq)]
 pub enum Action {
     Hold,
-    Move { to: Province, convoyed: bool },
-    SupportHold { to: Province },
-    SupportMove { from: Province, to: Province },
+    Move { to: Location, convoyed: bool },
+    SupportHold { to: Province, supported: Option<UnitType> },
+    SupportMove { from: Province, to: Province, supported: Option<UnitType> },
     Convoy { from: Province, to: Province }
 }
 
-// an Order stores the power that ordered it, which province is being ordered,
-//   the actual order (action), and some meta information for the resolve() and
-//   adjudicate() functions
+// an Order stores the power that ordered it, which province is being ordered
+//   (and the type and coast of the unit there), the actual order (action),
+//   and some meta information for the resolve() and adjudicate() functions
 // it is separate from a Retreat and an Adjust
+// it's displayed in standard notation, with the owner in front when the
+//   alternate flag is given (and the same is true of Retreats and Adjusts)
+// ex. "F Nth C A Lon - Nwy", with {:#} "England: F Nth C A Lon - Nwy"
 #[derive(Serialize,Deserialize,Clone,Debug)]
 pub struct Order {
     pub owner: Power,
     pub province: Province,
+    pub unit_type: UnitType,
+    pub coast: Option<char>,
     pub action: Action,
     pub resolution: bool,
     pub state: OrderState,
     pub id: usize
 }
 
+// a UnitOrder is what one of a power's units is ordered to do so far this
+//   phase (see Stpsyr::orders_for): the order it was given, or a hold if it
+//   hasn't been given one yet
+#[derive(Clone,Debug)]
+pub struct UnitOrder {
+    pub order: Order,
+    pub given: bool
+}
+
 // utility type for Retreat, corresponding to Action for Order
-#[derive(Serialize,Deserialize)]
+#[derive(Serialize,Deserialize,Clone,Debug,PartialEq)]
 pub enum RetreatAction {
     Disband,
-    Move { to: Province }
+    Move { to: Location }
 }
 
-// a Retreat stores the power that ordered it, which province to retreat from,
-//   and what to do with it (disband or move)
-#[derive(Serialize,Deserialize)]
+// a Retreat stores the power that ordered it, which province to retreat from
+//   (and the type and coast of the unit retreating), and what to do with it
+//   (disband or move)
+// ex. "A Mun R Boh", "F Spa/sc D"
+#[derive(Serialize,Deserialize,Clone,Debug)]
 pub struct Retreat {
     pub owner: Power,
     pub province: Province,
+    pub unit_type: UnitType,
+    pub coast: Option<char>,
     pub action: RetreatAction
 }
 
-#[derive(Serialize,Deserialize)]
+#[derive(Serialize,Deserialize,Clone,Debug,PartialEq)]
 pub enum AdjustAction {
     Disband,
-    Build { unit_type: UnitType }
+    Build { unit_type: UnitType, coast: Option<char> }
 }
 
 // a Adjust stores the power that ordered it, which province to build/destroy
 // in, and what to do there (disband or build a unit)
-#[derive(Serialize,Deserialize)]
+// ex. "Build F StP/nc", "Destroy Par"
+#[derive(Serialize,Deserialize,Clone,Debug)]
 pub struct Adjust {
     pub owner: Power,
     pub province: Province,
     pub action: AdjustAction
 }
 
+impl fmt::Display for Order {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() { write!(f, "{:?}: ", self.owner)?; }
+        write!(f, "{} {}", self.unit_type, Location {
+            province: self.province.clone(), coast: self.coast
+        })?;
+        match self.action {
+            Action::Hold => write!(f, " H"),
+            Action::Move { ref to, convoyed } =>
+                write!(f, " - {}{}", to, if convoyed { " via Convoy" } else { "" }),
+            Action::SupportHold { ref to, supported } =>
+                write!(f, " S {}{}", supported_type(supported), to),
+            Action::SupportMove { ref from, ref to, supported } =>
+                write!(f, " S {}{} - {}", supported_type(supported), from, to),
+            // (only armies can be convoyed)
+            Action::Convoy { ref from, ref to } =>
+                write!(f, " C A {} - {}", from, to)
+        }
+    }
+}
+// the type of a supported unit as it's written before it, if it's known
+fn supported_type(unit_type: Option<UnitType>) -> String {
+    unit_type.map_or(String::new(), |t| format!("{} ", t))
+}
+impl fmt::Display for Retreat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() { write!(f, "{:?}: ", self.owner)?; }
+        write!(f, "{} {}", self.unit_type, Location {
+            province: self.province.clone(), coast: self.coast
+        })?;
+        match self.action {
+            RetreatAction::Disband => write!(f, " D"),
+            RetreatAction::Move { ref to } => write!(f, " R {}", to)
+        }
+    }
+}
+impl fmt::Display for Adjust {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() { write!(f, "{:?}: ", self.owner)?; }
+        match self.action {
+            AdjustAction::Disband => write!(f, "Destroy {}", self.province),
+            AdjustAction::Build { unit_type, coast } =>
+                write!(f, "Build {} {}", unit_type, Location {
+                    province: self.province.clone(), coast
+                })
+        }
+    }
+}
+
+// a ParsedOrder is what a line given to Stpsyr::parse was understood as,
+//   which is one of the three kinds of order depending on the phase, or a
+//   build that's waived (which doesn't need an order of its own)
+// the unit type is the one the line gave, if any (builds always give one as
+//   part of their action)
+#[derive(Clone,Debug,PartialEq)]
+pub enum ParsedOrder {
+    Order { province: Province, unit_type: Option<UnitType>, action: Action },
+    Retreat { province: Province, unit_type: Option<UnitType>, action: RetreatAction },
+    Adjust { province: Province, unit_type: Option<UnitType>, action: AdjustAction },
+    Waive
+}
+
+// a ParseError is what was wrong with a line given to Stpsyr::parse, along with
+//   the columns of the line it's about (counted in characters from 0)
+// ex. "A ber S" is Unparseable at 6..7, since it's missing what to support
+#[derive(Clone,Debug,PartialEq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub span: Range<usize>
+}
+#[derive(Clone,Debug,PartialEq)]
+pub enum ParseErrorKind {
+    // the text doesn't fit the grammar of an order (or is missing something)
+    Unparseable,
+    // a province that isn't on the map, with the provinces (and their full
+    //   names) it's closest to, best first, in case it was a typo
+    // ex. "burgandy" => [(bur, "Burgundy")]
+    UnknownProvince { suggestions: Vec<(Province, String)> },
+    // the start of the names of more than one province
+    // ex. "nor" => Norway, North Sea, North Africa, ...
+    AmbiguousProvince { matches: Vec<(Province, String)> },
+    // a power that isn't in the game
+    UnknownPower,
+    // an order that was understood, but isn't allowed (ex. a move to somewhere
+    //   the unit can't reach, or an order for someone else's unit)
+    Rejected,
+    // an order that gives the wrong type for its unit (see UnitTypeCheck)
+    WrongUnitType
+}
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // ex. "Brest (bre), Burgundy (bur) or Prussia (pru)"
+        let provinces = |provinces: &[(Province, String)]| {
+            let mut names: Vec<String> = provinces.iter()
+                .map(|(p, name)| format!("{} ({})", name, p.name)).collect();
+            let last = names.pop().unwrap_or_default();
+            if names.is_empty() { last } else { format!("{} or {}", names.join(", "), last) }
+        };
+        let message = match self.kind {
+            ParseErrorKind::Unparseable => String::from("this isn't an order"),
+            ParseErrorKind::UnknownProvince { ref suggestions } if suggestions.is_empty() =>
+                String::from("there's no such province"),
+            ParseErrorKind::UnknownProvince { ref suggestions } =>
+                format!("there's no such province; did you mean {}?", provinces(suggestions)),
+            ParseErrorKind::AmbiguousProvince { ref matches } =>
+                format!("this could be {}", provinces(matches)),
+            ParseErrorKind::UnknownPower => String::from("there's no such power"),
+            ParseErrorKind::Rejected => String::from("this order isn't allowed"),
+            ParseErrorKind::WrongUnitType => String::from("that's not the type of this unit")
+        };
+        write!(f, "{} (columns {} to {})", message, self.span.start, self.span.end)
+    }
+}
+
+// a Diagnostic is the outcome of one line given to Stpsyr::parse: the line
+//   itself (and its number, counting from 1), the order it was understood
+//   as, what went wrong with it if it wasn't used, and anything questionable
+//   about it if it was
+// it also has the keywords that were recognized in the line, and where
+// ex. "F lon-eng" on line 1, understood as a Move, with no error, and the
+//   keyword Fleet at 0..1
+#[derive(Clone,Debug,PartialEq)]
+pub struct Diagnostic {
+    pub number: usize,
+    pub line: String,
+    pub order: Option<ParsedOrder>,
+    pub error: Option<ParseError>,
+    pub warnings: Vec<ParseError>,
+    pub keywords: Vec<(Keyword, Range<usize>)>
+}
+
+// a Locale is the words players use in orders in some language (see
+//   locale.rs): the words for each Keyword, words that don't mean anything
+//   in an order, and more names for provinces
+// ex. German, where "u" and "unterstützt" mean Support, and "München" is mun
+#[derive(Clone,Debug,PartialEq,Default)]
+pub struct Locale {
+    pub name: String,
+    pub keywords: Vec<(Keyword, String)>,
+    pub ignored: Vec<String>,
+    pub aliases: Vec<(Province, String)>
+}
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum Keyword {
+    Army, Fleet, Hold, Move, Support, Convoy, Via, Coast,
+    Retreat, Disband, Build, Destroy, Waive
+}
+
+// a DaideOrder is an order written in DAIDE, the language Diplomacy bots
+//   speak, which always gives the owner and type of the unit being ordered
+//   (so it's read into an Order, Retreat or Adjust, along with the type of
+//   the unit for removals, which an Adjust doesn't keep); powers can also
+//   waive builds, which doesn't need an order of our own
+// ex. "( ENG FLT LON ) MTO NTH", "( FRA AMY PAR ) SUP ( FRA AMY MAR ) MTO BUR"
+#[derive(Clone,Debug)]
+pub enum DaideOrder {
+    Order(Order),
+    Retreat(Retreat),
+    Adjust(Adjust),
+    Remove(Adjust, UnitType),
+    Waive(Power)
+}
+
+// an OrderDocument holds every power's orders for one or more turns, as a GM
+//   might collect them in a single file (see document.rs for the format)
+// each Turn can say which phase and year it's for, and has the orders of any
+//   number of powers, one line per order
+#[derive(Clone,Debug,PartialEq,Default)]
+pub struct OrderDocument {
+    pub turns: Vec<Turn>
+}
+#[derive(Clone,Debug,PartialEq,Default)]
+pub struct Turn {
+    pub phase: Option<(Phase, i32)>,
+    pub submissions: Vec<Submission>
+}
+#[derive(Clone,Debug,PartialEq)]
+pub struct Submission {
+    pub power: Power,
+    pub orders: Vec<String>
+}
+
+// a DocumentError is something wrong with an order document, found either
+//   when reading it (these have the number of the line, counting from 1) or
+//   when playing it
+#[derive(Clone,Debug,PartialEq)]
+pub enum DocumentError {
+    // a heading for a power that isn't in the game
+    UnknownPower { line: usize, name: String },
+    // an order that comes before any power's heading
+    MissingPower { line: usize },
+    // a phase header that isn't a phase and a year
+    BadPhase { line: usize },
+    // a turn for a phase that isn't the one the game is in
+    WrongPhase { phase: Phase, year: i32 }
+}
+impl fmt::Display for DocumentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DocumentError::UnknownPower { line, ref name } =>
+                write!(f, "line {}: there's no power called {}", line, name),
+            DocumentError::MissingPower { line } =>
+                write!(f, "line {}: this order isn't under any power", line),
+            DocumentError::BadPhase { line } =>
+                write!(f, "line {}: this isn't a phase and year", line),
+            DocumentError::WrongPhase { phase, year } =>
+                write!(f, "the orders for {:?} {} came at the wrong time", phase, year)
+        }
+    }
+}
+
 // fairly self-explanatory
 #[derive(Serialize,Deserialize,Clone,Copy,Debug,PartialEq)]
 pub enum Phase {
@@ -181,19 +623,119 @@ pub enum Phase {
     SpringRetreats,
     FallDiplomacy,
     FallRetreats,
-    Builds
+    Builds,
+    GameOver
+}
+
+// what a draw or concession proposal is asking for
+#[derive(Serialize,Deserialize,Clone,Debug,PartialEq)]
+pub enum ProposalKind {
+    // a draw including all surviving powers
+    DrawAll,
+    // a draw including only the named powers
+    Draw { powers: Vec<Power> },
+    // everyone else concedes the game to a single power
+    Concession { to: Power }
+}
+
+// a Proposal stores what's being proposed, who proposed it, the phase it was
+//   proposed in (it lapses once that phase is over), and the votes so far
+//   (whether each power accepted it)
+#[derive(Serialize,Deserialize,Clone,Debug)]
+pub struct Proposal {
+    pub kind: ProposalKind,
+    pub proposer: Power,
+    pub phase: Phase,
+    pub year: i32,
+    pub votes: Vec<(Power, bool)>
+}
+
+// how a game ended, once its phase is GameOver
+#[derive(Serialize,Deserialize,Clone,Debug,PartialEq)]
+pub enum GameResult {
+    Solo { power: Power, year: i32 },
+    Draw { powers: Vec<Power>, year: i32 },
+    Concession { to: Power, year: i32 }
+}
+
+// a PowerInfo is what a variant says about one of its powers
+// ex. Austria, Austrian, A, c48f85
+#[derive(Serialize,Deserialize,Clone,Debug)]
+pub struct PowerInfo {
+    pub power: Power,
+    pub adjective: String,
+    pub abbreviation: String,
+    pub color: String
+}
+
+// where powers are allowed to build (they always have to own the SC, and it
+//   has to be empty)
+#[derive(Serialize,Deserialize,Clone,Copy,Debug,PartialEq,Default)]
+pub enum BuildRule {
+    // only in their home centers (the standard rule)
+    #[default]
+    HomeCenters,
+    // in any SC (Build Anywhere, and Chaos)
+    Anywhere,
+    // in their home centers, and in any SC they've ever held after a fall
+    Conquest
+}
+
+// what to do with an order that gives the wrong type for the unit it's for
+// ex. "F par-bur" for an army in Paris
+#[derive(Serialize,Deserialize,Clone,Copy,Debug,PartialEq,Default)]
+pub enum UnitTypeCheck {
+    // the order isn't used
+    Reject,
+    // the order is used, but the mistake is reported in its Diagnostic
+    #[default]
+    Warn,
+    // the order is used as if the type were right
+    Ignore
+}
+
+// optional rules that a variant (or a single game) can turn on
+#[derive(Serialize,Deserialize,Clone,Debug,Default)]
+pub struct Rules {
+    // the game ends in a draw between all survivors after this year
+    pub end_year: Option<i32>,
+    pub builds: BuildRule,
+    // each power only gets to see what's near its own units and SCs
+    pub fog_of_war: bool,
+    pub unit_types: UnitTypeCheck
+}
+
+// a Variant bundles everything needed to start a game: the map (as it is at
+//   the start of the game), the artwork to render it with, the powers, the
+//   starting year, how many SCs are needed to win, and the rules
+// a copy of it is kept in each game, so that saved games can be loaded
+//   without the variant files around (except the artwork)
+#[derive(Serialize,Deserialize,Clone,Debug)]
+pub struct Variant {
+    pub name: String,
+    pub map: Vec<MapRegion>,
+    pub svg: String,
+    pub powers: Vec<PowerInfo>,
+    pub start_year: i32,
+    pub victory: u32,
+    pub rules: Rules
 }
 
 // this is the main struct (duh)
-#[derive(Serialize,Deserialize)]
+#[derive(Serialize,Deserialize,Clone)]
 pub struct Stpsyr {
+    pub variant: Variant,
     pub map: Vec<MapRegion>,
     pub orders: Vec<Order>,
+    pub resolved: Vec<Order>,
     pub retreats: Vec<Retreat>,
     pub adjusts: Vec<Adjust>,
     pub dependencies: Vec<usize>,
     pub dislodged: Vec<(Province, Unit)>,
     pub contested: HashSet<Province>,
+    pub powers: Vec<(Power, PowerStatus)>,
+    pub proposals: Vec<Proposal>,
+    pub result: Option<GameResult>,
     pub phase: Phase,
     pub year: i32
 }
@@ -14,6 +14,74 @@ impl Stpsyr {
         self.map.iter().find(|r| r.province == *province)
     }
 
+    // find a province by its short name, full name or any of its aliases
+    //   (ignoring case)
+    // ex. "stp", "St. Petersburg", "spb"
+    pub fn find_province(&self, name: &str) -> Option<Province> {
+        let name = name.trim().to_lowercase();
+        self.map.iter().find(|r| r.province.name == name ||
+                r.full_name.to_lowercase() == name ||
+                r.aliases.iter().any(|a| a.to_lowercase() == name))
+            .map(|r| Province::from(r.province.name.clone()))
+    }
+
+    // get the name of a province to show to players
+    // ex. "St. Petersburg"
+    pub fn full_name(&self, province: &Province) -> String {
+        self.get_region(province)
+            .map_or(province.name.clone(), |r| r.full_name.clone())
+    }
+
+    // get the name of a location to show to players, including the coast
+    // ex. "St. Petersburg (south coast)"
+    pub fn location_name(&self, location: &Location) -> String {
+        let name = self.full_name(&location.province);
+        match location.coast {
+            Some('n') => format!("{} (north coast)", name),
+            Some('s') => format!("{} (south coast)", name),
+            Some('e') => format!("{} (east coast)", name),
+            Some('w') => format!("{} (west coast)", name),
+            Some(c) => format!("{} ({}c)", name, c),
+            None => name
+        }
+    }
+
+    // describe the orders from the last diplomacy phase and how they went,
+    //   one line per order, using full province names
+    // ex. "Italy: Venice - Tyrolia (succeeds)"
+    pub fn describe_results(&self) -> Vec<String> {
+        self.resolved.iter().map(|o| {
+            let order = match o.action {
+                Action::Hold => String::from("holds"),
+                Action::Move { ref to, convoyed } => format!("- {}{}",
+                    self.location_name(to), if convoyed { " via convoy" } else { "" }),
+                Action::SupportHold { ref to, .. } =>
+                    format!("supports {}", self.full_name(to)),
+                Action::SupportMove { ref from, ref to, .. } =>
+                    format!("supports {} - {}", self.full_name(from),
+                        self.full_name(to)),
+                Action::Convoy { ref from, ref to } =>
+                    format!("convoys {} - {}", self.full_name(from),
+                        self.full_name(to))
+            };
+            format!("{:?}: {} {} ({})", o.owner, self.full_name(&o.province),
+                order, if o.resolution { "succeeds" } else { "fails" })
+        }).collect()
+    }
+
+    // whether units can ever go into a province
+    pub fn is_passable(&self, province: &Province) -> bool {
+        self.get_region(province)
+            .is_some_and(|r| r.kind != ProvinceKind::Impassable)
+    }
+
+    // get whether the order given to the unit in a province during the last
+    //   diplomacy phase succeeded, or None if it wasn't given a (legal) order
+    pub fn order_result(&self, province: &Province) -> Option<bool> {
+        self.resolved.iter().find(|o| o.province == *province)
+            .map(|o| o.resolution)
+    }
+
     // get counts of SCs owned for each power
     pub fn sc_counts(&self) -> HashMap<Power, u32> {
         let mut counts = HashMap::new();
@@ -31,11 +99,12 @@ impl Stpsyr {
         counts
     }
 
-    // get counts of units for each power
+    // get counts of units for each power (not counting neutral garrisons)
     pub fn unit_counts(&self) -> HashMap<Power, u32> {
         let mut counts = HashMap::new();
         for r in &self.map {
             if let Some(ref u) = r.unit {
+                if u.owner.is_neutral() { continue; }
                 if let Some(count) = counts.get_mut(&u.owner) {
                     *count += 1;
                     continue;
@@ -46,17 +115,115 @@ impl Stpsyr {
         counts
     }
 
+    // get the status of a power, or None if it isn't playing in this game
+    pub fn power_status(&self, power: &Power) -> Option<PowerStatus> {
+        self.powers.iter().find(|(p, _)| p == power).map(|&(_, s)| s)
+    }
+
+    // whether orders from this power should be accepted (eliminated powers,
+    //   powers in civil disorder, neutrals and powers that aren't in the game
+    //   don't get to give any)
+    pub fn can_order(&self, power: &Power) -> bool {
+        !power.is_neutral() && self.power_status(power) == Some(PowerStatus::Active)
+    }
+
+    // get a power that's giving orders under the name this game uses for it,
+    //   or None if its orders shouldn't be accepted (see can_order)
+    pub fn orderer(&self, power: &Power) -> Option<Power> {
+        if !self.can_order(power) { return None; }
+        self.powers.iter().find(|(p, _)| p == power).map(|(p, _)| p.clone())
+    }
+
+    // put a power into civil disorder (or take it back out, e.g. when a
+    //   replacement player is found)
+    pub fn set_civil_disorder(&mut self, power: &Power, disorder: bool) {
+        for (p, status) in &mut self.powers {
+            if p == power {
+                match *status {
+                    PowerStatus::Eliminated { .. } => {},
+                    _ => *status = if disorder {
+                        PowerStatus::CivilDisorder
+                    } else {
+                        PowerStatus::Active
+                    }
+                }
+            }
+        }
+    }
+
+    // get a standings table: every power with its status and SC and unit
+    //   counts, sorted by number of SCs
+    pub fn standings(&self) -> Vec<Standing> {
+        let (sc_counts, unit_counts) = (self.sc_counts(), self.unit_counts());
+        let mut standings: Vec<Standing> = self.powers.iter()
+            .map(|(power, status)| Standing {
+                power: power.clone(),
+                status: *status,
+                centers: *sc_counts.get(power).unwrap_or(&0),
+                units: *unit_counts.get(power).unwrap_or(&0)
+            }).collect();
+        standings.sort_by(|a, b| b.centers.cmp(&a.centers)
+            .then(b.units.cmp(&a.units)));
+        standings
+    }
+
+    // a power is eliminated once it has no units (counting dislodged ones)
+    //   and no SCs left
+    fn update_eliminations(&mut self) {
+        let (sc_counts, unit_counts) = (self.sc_counts(), self.unit_counts());
+        for (p, status) in &mut self.powers {
+            if let PowerStatus::Eliminated { .. } = *status { continue; }
+            if !sc_counts.contains_key(p) && !unit_counts.contains_key(p) &&
+                    !self.dislodged.iter().any(|(_, u)| u.owner == *p) {
+                *status = PowerStatus::Eliminated { year: self.year };
+            }
+        }
+    }
+
     pub fn next_phase(&mut self) {
         // update ownership
         for ref mut r in &mut self.map {
             if !r.sc || self.phase == Phase::FallDiplomacy ||
                     self.phase == Phase::FallRetreats {
                 if let Some(ref unit) = r.unit {
+                    // neutral garrisons don't take over anything
+                    if unit.owner.is_neutral() { continue; }
                     r.owner = Some(unit.owner.clone());
+                    // with the conquest build rule, SCs held after a fall
+                    //   become extra home centers
+                    if r.sc && self.variant.rules.builds == BuildRule::Conquest &&
+                            r.home_power.as_ref() != Some(&unit.owner) &&
+                            !r.conquered_by.contains(&unit.owner) {
+                        r.conquered_by.push(unit.owner.clone());
+                    }
                 }
             }
         }
 
+        self.update_eliminations();
+
+        // a power with enough SCs once the fall turn is over (after its
+        //   retreats, if there are any) wins outright, as long as nobody has
+        //   as many as it does (a tie at the top isn't a solo for anyone)
+        if self.phase == Phase::FallRetreats ||
+                (self.phase == Phase::FallDiplomacy && self.dislodged.is_empty()) {
+            let counts = self.sc_counts();
+            let most = counts.values().cloned().max().unwrap_or(0);
+            let leaders: Vec<&Power> = counts.iter()
+                .filter(|&(_, &count)| count == most).map(|(p, _)| p).collect();
+            let winner = if leaders.len() == 1 && most >= self.variant.victory {
+                Some(leaders[0].clone())
+            } else { None };
+            if let Some(power) = winner {
+                let year = self.year;
+                self.end_game(GameResult::Solo { power, year });
+                return;
+            }
+        }
+
+        // proposals only stand for the phase they were made in
+        self.proposals = vec![];
+
         self.phase = match self.phase {
             Phase::SpringDiplomacy => if self.dislodged.is_empty() {
                 Phase::FallDiplomacy
@@ -69,13 +236,22 @@ impl Stpsyr {
                     if self.sc_counts() != self.unit_counts() {
                         Phase::Builds
                     } else {
+                        self.year += 1;
                         Phase::SpringDiplomacy
                     }
                 } else {
                     Phase::FallRetreats
                 },
-            Phase::Builds => { self.year += 1; Phase::SpringDiplomacy }
+            Phase::Builds => { self.year += 1; Phase::SpringDiplomacy },
+            Phase::GameOver => Phase::GameOver
         };
+
+        // games with an end year are drawn between the survivors once it's over
+        if self.phase == Phase::SpringDiplomacy &&
+                self.variant.rules.end_year.is_some_and(|y| self.year > y) {
+            let (powers, year) = (self.survivors(), self.year - 1);
+            self.end_game(GameResult::Draw { powers, year });
+        }
     }
 
 }
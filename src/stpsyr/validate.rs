@@ -0,0 +1,127 @@
+use stpsyr::types::*;
+
+impl Variant {
+
+    // check the variant's map for mistakes (see validate_map)
+    pub fn validate(&self) -> Vec<MapProblem> {
+        validate_map(&self.map)
+    }
+
+}
+
+// check that a map is consistent, returning every problem found (so an empty
+//   list means the map is fine)
+// every border has to go both ways, with the same coasts on each end, and lead
+//   somewhere that exists; every home province has to be an SC; and every
+//   starting unit has to be able to move, and has to start on one of its
+//   owner's home SCs (or an unowned SC, for neutrals)
+pub fn validate_map(map: &[MapRegion]) -> Vec<MapProblem> {
+    let mut problems = vec![];
+
+    for region in map {
+        let from = Location::from(region.province.clone());
+
+        for border in &region.fleet_borders {
+            // the coast this border leaves from, for multi-coast provinces
+            let from = Location { coast: border.from_coast, ..from.clone() };
+            let to = border.to.clone();
+
+            let other = match map.iter().find(|r| r.province == to.province) {
+                Some(other) => other,
+                None => {
+                    problems.push(MapProblem::DanglingBorder {
+                        from, to, unit_type: UnitType::Fleet
+                    });
+                    continue;
+                }
+            };
+
+            let coasts = other.coasts();
+            match to.coast {
+                Some(coast) if !coasts.contains(&coast) => {
+                    problems.push(MapProblem::DanglingBorder {
+                        from, to, unit_type: UnitType::Fleet
+                    });
+                    continue;
+                },
+                None if !coasts.is_empty() => {
+                    problems.push(MapProblem::MissingCoast { from, to });
+                    continue;
+                },
+                _ => {}
+            }
+
+            if !other.fleet_borders.iter().any(|b| b.to == from &&
+                    b.from_coast == to.coast) {
+                problems.push(MapProblem::AsymmetricBorder {
+                    from, to, unit_type: UnitType::Fleet
+                });
+            }
+        }
+
+        for border in &region.army_borders {
+            let to = Location::from(border.clone());
+            match map.iter().find(|r| r.province == *border) {
+                None => problems.push(MapProblem::DanglingBorder {
+                    from: from.clone(), to, unit_type: UnitType::Army
+                }),
+                Some(other) => if !other.army_borders.contains(&region.province) {
+                    problems.push(MapProblem::AsymmetricBorder {
+                        from: from.clone(), to, unit_type: UnitType::Army
+                    });
+                }
+            }
+        }
+
+        // the kind has to agree with the borders: a sea can't have army
+        //   borders, and so on (impassable provinces can have any borders,
+        //   since other provinces might want to border them to draw the map)
+        if match region.kind {
+            ProvinceKind::Land => !region.fleet_borders.is_empty(),
+            ProvinceKind::Sea => !region.army_borders.is_empty(),
+            ProvinceKind::Coastal => region.fleet_borders.is_empty() ||
+                region.army_borders.is_empty(),
+            ProvinceKind::Island => region.fleet_borders.is_empty() ||
+                !region.army_borders.is_empty(),
+            ProvinceKind::Impassable => false
+        } || (region.canal && region.kind != ProvinceKind::Coastal) ||
+                (region.convoy && !region.kind.allows(UnitType::Fleet)) {
+            problems.push(MapProblem::WrongKind {
+                province: region.province.clone(),
+                kind: region.kind
+            });
+        }
+
+        if let Some(ref power) = region.home_power {
+            if !region.sc {
+                problems.push(MapProblem::HomeNotCenter {
+                    province: region.province.clone(),
+                    power: power.clone()
+                });
+            }
+        }
+
+        if let Some(ref unit) = region.unit {
+            if !region.kind.allows(unit.unit_type) {
+                problems.push(MapProblem::BadStartingUnit {
+                    province: region.province.clone(),
+                    unit_type: unit.unit_type
+                });
+            }
+            // (neutral garrisons go in SCs that aren't anyone's home; a home
+            //   that isn't an SC is reported above)
+            if if unit.owner.is_neutral() {
+                !region.sc || region.home_power.is_some()
+            } else {
+                region.home_power.as_ref() != Some(&unit.owner)
+            } {
+                problems.push(MapProblem::StartingUnitOffCenter {
+                    province: region.province.clone(),
+                    owner: unit.owner.clone()
+                });
+            }
+        }
+    }
+
+    problems
+}
@@ -0,0 +1,266 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use stpsyr::types::*;
+
+extern crate csv;
+
+impl Variant {
+
+    // load a variant file, which looks like this:
+    //
+    //     # comments start with a hash
+    //     name: Standard
+    //     map: standard.csv
+    //     svg: standard.svg
+    //     year: 1901
+    //     victory: 18
+    //     power: Austria, Austrian, A, c48f85
+    //     rule: end-year 1910
+    //     rule: builds anywhere
+    //
+    // with one power line for each power (giving its name, adjective,
+    //   abbreviation and color), in the order they should be listed in, and
+    //   any number of rule lines; the map and svg paths are relative to the
+    //   variant file
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Variant> {
+        let path = path.as_ref();
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData,
+            format!("{}: {}", path.display(), msg));
+
+        let (mut name, mut map, mut svg) = (None, None, None);
+        let (mut start_year, mut victory) = (None, None);
+        let mut powers = vec![];
+        let mut rules = Rules::default();
+
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') { continue; }
+
+            let (key, value) = if let Some(idx) = line.find(':') {
+                (line[..idx].trim(), line[idx+1..].trim())
+            } else { return Err(invalid(format!("bad line \"{}\"", line))); };
+
+            match key {
+                "name" => name = Some(value.to_string()),
+                "map" => map = Some(load_map(dir.join(value))?),
+                "svg" => svg = Some(dir.join(value).to_string_lossy().into_owned()),
+                "year" => start_year = value.parse().ok(),
+                "victory" => victory = value.parse().ok(),
+                "power" => {
+                    let fields: Vec<&str> = value.split(',').map(|f| f.trim())
+                        .collect();
+                    if fields.len() != 4 {
+                        return Err(invalid(format!("bad power \"{}\"", value)));
+                    }
+                    if Power::from(fields[0]).is_neutral() {
+                        return Err(invalid(format!("\"{}\" is reserved for neutral \
+                            garrisons", fields[0])));
+                    }
+                    powers.push(PowerInfo {
+                        power: Power::from(fields[0]),
+                        adjective: fields[1].to_string(),
+                        abbreviation: fields[2].to_string(),
+                        color: fields[3].to_string()
+                    });
+                },
+                "rule" => if !rules.set(value) {
+                    return Err(invalid(format!("unknown rule \"{}\"", value)));
+                },
+                _ => return Err(invalid(format!("unknown key \"{}\"", key)))
+            }
+        }
+
+        Ok(Variant {
+            name: name.ok_or_else(|| invalid(String::from("missing name")))?,
+            map: map.ok_or_else(|| invalid(String::from("missing map")))?,
+            svg: svg.ok_or_else(|| invalid(String::from("missing svg")))?,
+            powers,
+            start_year: start_year
+                .ok_or_else(|| invalid(String::from("missing or bad year")))?,
+            victory: victory
+                .ok_or_else(|| invalid(String::from("missing or bad victory")))?,
+            rules
+        })
+    }
+
+    // get what the variant says about a power
+    pub fn power_info(&self, power: &Power) -> Option<&PowerInfo> {
+        self.powers.iter().find(|p| p.power == *power)
+    }
+
+    // find one of the variant's powers by its name, adjective or
+    //   abbreviation (ignoring case)
+    // ex. "Austria", "austrian", "A"
+    pub fn find_power(&self, name: &str) -> Option<Power> {
+        let name = name.trim().to_lowercase();
+        self.powers.iter().find(|p| p.power.name.to_lowercase() == name ||
+                p.adjective.to_lowercase() == name ||
+                p.abbreviation.to_lowercase() == name)
+            .map(|p| p.power.clone())
+    }
+
+}
+
+impl Rules {
+
+    // turn on a rule given as it's written in a variant file, returning
+    //   whether it was recognized
+    // ex. "end-year 1910", "builds anywhere", "fog-of-war", "unit-types reject"
+    pub fn set(&mut self, rule: &str) -> bool {
+        let mut parts = rule.split_whitespace();
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some("end-year"), Some(year), None) => {
+                self.end_year = year.parse().ok();
+                self.end_year.is_some()
+            },
+            (Some("fog-of-war"), None, None) => {
+                self.fog_of_war = true;
+                true
+            },
+            (Some("builds"), Some(builds), None) => {
+                self.builds = match builds {
+                    "home" => BuildRule::HomeCenters,
+                    "anywhere" => BuildRule::Anywhere,
+                    "conquest" => BuildRule::Conquest,
+                    _ => return false
+                };
+                true
+            },
+            (Some("unit-types"), Some(check), None) => {
+                self.unit_types = match check {
+                    "reject" => UnitTypeCheck::Reject,
+                    "warn" => UnitTypeCheck::Warn,
+                    "ignore" => UnitTypeCheck::Ignore,
+                    _ => return false
+                };
+                true
+            },
+            _ => false
+        }
+    }
+
+}
+
+// parse a map file as CSV to generate the map
+pub fn load_map<P: AsRef<Path>>(mapfile: P) -> io::Result<Vec<MapRegion>> {
+    let mut reader = csv::Reader::from_file(mapfile)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    let mut map: Vec<MapRegion> = Vec::new();
+    for region in reader.decode::<(
+                String,          // 0 name
+                bool,            // 1 SC?
+                Option<String>,  // 2 starting owner
+                Option<String>,  // 3 starting unit type
+                String,          // 4 bordering provinces (fleets)
+                String,          // 5 bordering provinces (armies)
+                Option<String>,  // 6 full name
+                Option<String>,  // 7 aliases, separated by semicolons
+                Option<String>,  // 8 kind (worked out from the borders if empty)
+                Option<bool>,    // 9 canal?
+                Option<bool>     // 10 convoys allowed? (only at sea if empty)
+            )>() {
+        let region = region
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        // each coast of a multi-coast province gets its own row, with the
+        //   fleet borders from that coast
+        let Location { province, coast } = Location::from(region.0.clone());
+
+        let fleet_borders: Vec<Border> = region.4.split_whitespace()
+            .map(|p| Border { from_coast: coast, to: Location::from(p) })
+            .collect();
+        let army_borders = region.5.split_whitespace().map(Province::from)
+            .collect();
+
+        let unit = match region.3 {
+            // a unit with no owner is a neutral garrison
+            Some(ref unit_type) => Some(Unit {
+                owner: region.2.clone().map_or_else(Power::neutral, Power::from),
+                unit_type: match &unit_type[..] {
+                    "Army" => UnitType::Army,
+                    "Fleet" => UnitType::Fleet,
+                    _ => return Err(io::Error::new(io::ErrorKind::InvalidData,
+                        "unit type must be Army or Fleet"))
+                },
+                coast
+            }),
+            None => None
+        };
+
+        if let Some(existing_region) = map.iter_mut()
+                .find(|r| r.province == province) {
+            existing_region.fleet_borders.extend(fleet_borders.iter().cloned());
+            if unit.is_some() { existing_region.unit = unit; }
+            continue;
+        }
+
+        let kind = match region.8.as_ref().map(|k| &k[..]) {
+            Some("land") => ProvinceKind::Land,
+            Some("sea") => ProvinceKind::Sea,
+            Some("coastal") => ProvinceKind::Coastal,
+            Some("island") => ProvinceKind::Island,
+            Some("impassable") => ProvinceKind::Impassable,
+            Some(_) => return Err(io::Error::new(io::ErrorKind::InvalidData,
+                "kind must be land, sea, coastal, island or impassable")),
+            None => match (fleet_borders.is_empty(), region.5.trim().is_empty()) {
+                (false, false) => ProvinceKind::Coastal,
+                (false, true) => ProvinceKind::Sea,
+                (true, false) => ProvinceKind::Land,
+                (true, true) => ProvinceKind::Impassable
+            }
+        };
+
+        map.push(MapRegion {
+            kind,
+            canal: region.9.unwrap_or(false),
+            convoy: region.10.unwrap_or(kind == ProvinceKind::Sea),
+            conquered_by: vec![],
+            full_name: region.6.clone().unwrap_or_else(|| province.name.clone()),
+            aliases: region.7.as_ref().map_or(vec![], |a| a.split(';')
+                .map(|a| a.trim().to_string()).filter(|a| !a.is_empty())
+                .collect()),
+            province,
+            sc: region.1,
+
+            // (a power's territory that isn't an SC starts out owned by it, but
+            //   only its SCs and where its units start are its homes, so
+            //   validate_map can tell when one of those isn't an SC)
+            owner: region.2.clone().map(Power::from),
+            home_power: region.2.clone().filter(|_| region.1 || region.3.is_some())
+                .map(Power::from),
+            unit,
+
+            fleet_borders,
+            army_borders
+        });
+    };
+
+    open_canals(&mut map);
+
+    Ok(map)
+}
+
+// fleets can go through canals from any side, so forget about any coasts
+//   they were given
+pub(crate) fn open_canals(map: &mut [MapRegion]) {
+    let canals: Vec<Province> = map.iter().filter(|r| r.canal)
+        .map(|r| r.province.clone()).collect();
+    for r in map.iter_mut() {
+        if r.canal {
+            let mut seen = vec![];
+            r.fleet_borders.retain(|b| !seen.contains(&b.to) && {
+                seen.push(b.to.clone());
+                true
+            });
+            for border in &mut r.fleet_borders { border.from_coast = None; }
+            if let Some(ref mut unit) = r.unit { unit.coast = None; }
+        }
+        for border in &mut r.fleet_borders {
+            if canals.contains(&border.to.province) { border.to.coast = None; }
+        }
+    }
+}
@@ -21,22 +21,174 @@ use stpsyr::*;
 
 use std::io::{BufRead, BufReader};
 use std::fs::File;
+use std::fmt;
+use std::panic;
+use std::ops::Range;
 
-fn test_from_file(filename: &str) {
-    let err_msg = "error parsing test cases";
-    let f = File::open(filename).expect(err_msg);
-    let file = BufReader::new(&f);
-    let mut title = String::new();
-    let mut s = Stpsyr::new("data/standard.csv");
+// why a test case didn't pass: one of its assertions didn't hold, it never
+//   got to the phase it was checking, or the adjudicator panicked
+// only the first of these can be a known failure
+enum Failure {
+    Assertion(String),
+    Setup(String),
+    Panic
+}
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Failure::Assertion(ref msg) | Failure::Setup(ref msg) => write!(f, "{}", msg),
+            Failure::Panic => write!(f, "adjudicator panicked")
+        }
+    }
+}
+
+// parse a unit as written in test files, ex. "Army Germany"
+fn parse_unit(unit: &str) -> (UnitType, Power) {
+    let mut parts = unit.split_whitespace();
+    let unit_type = match parts.next() {
+        Some("Army") => UnitType::Army,
+        Some("Fleet") => UnitType::Fleet,
+        _ => panic!("bad unit in test file: {}", unit)
+    };
+    (unit_type, Power::from(parts.next().expect("missing unit owner")))
+}
+
+// parse a phase and year as written in test files, ex. "FallRetreats 1901"
+fn parse_phase(phase: &str) -> (Phase, i32) {
+    let mut parts = phase.split_whitespace();
+    let p = match parts.next() {
+        Some("SpringDiplomacy") => Phase::SpringDiplomacy,
+        Some("SpringRetreats") => Phase::SpringRetreats,
+        Some("FallDiplomacy") => Phase::FallDiplomacy,
+        Some("FallRetreats") => Phase::FallRetreats,
+        Some("Builds") => Phase::Builds,
+        _ => panic!("bad phase in test file: {}", phase)
+    };
+    (p, parts.next().and_then(|y| y.parse().ok()).expect("missing year"))
+}
+
+// split a directive or assertion into its keyword (if there is one) and the
+//   rest, ex. "owner mun: Germany" into ("owner", "mun", "Germany")
+fn split_line(line: &str) -> (&str, &str, Option<&str>) {
+    let mut parts = line.splitn(2, ": ");
+    let head = parts.next().unwrap().trim();
+    let (keyword, subject) = match head.find(' ') {
+        Some(idx) => (&head[..idx], head[idx+1..].trim()),
+        None => ("", head)
+    };
+    (keyword, subject, parts.next().map(|p| p.trim()))
+}
+
+// apply a setup directive, which is a line starting with "@"
+// ex. "@ lon: Fleet England", "@ dislodged mun: Army Germany",
+//   "@ owner mun: Germany", "@ contested boh", "@ phase FallRetreats 1901"
+fn setup(s: &mut Stpsyr, directive: &str) {
+    let mut scenario = s.scenario();
+    let (keyword, subject, value) = split_line(directive);
+    let province = Province::from(subject);
+    match keyword {
+        "" => {
+            let (unit_type, owner) = parse_unit(value.expect("missing unit"));
+            scenario.unit(&Location::from(subject), &owner, unit_type);
+        },
+        "dislodged" => {
+            let (unit_type, owner) = parse_unit(value.expect("missing unit"));
+            scenario.dislodged(&Location::from(subject), &owner, unit_type);
+        },
+        "owner" => { scenario.owner(&province, value.map(Power::from).as_ref()); },
+        "contested" => { scenario.contested(&province); },
+        "phase" => {
+            let (phase, year) = parse_phase(subject);
+            scenario.phase(phase, year);
+        },
+        _ => panic!("bad setup directive in test file: {}", directive)
+    }
+}
+
+// check an assertion, which is any line with a colon in it
+// ex. "lon: Fleet England", "ven: empty", "order lon: succeeds",
+//   "order kie: void", "dislodged mun: Army Germany", "retreats mun: boh sil",
+//   "owner mun: Germany", "phase: FallRetreats 1901", "builds England: 1",
+//   "disbands Russia: 2"
+fn check(s: &Stpsyr, assertion: &str) -> Result<(), Failure> {
+    let format_unit = |u: Option<&Unit>|
+        u.map_or(String::from("empty"), |u| format!("{:?}", u));
+    let (keyword, subject, value) = split_line(assertion);
+    let expected = value.expect("error parsing test cases").to_string();
+    let province = Province::from(subject);
+
+    let found = match keyword {
+        "" if subject == "phase" => {
+            let (phase, year) = parse_phase(&expected);
+            if (phase, year) == (s.phase, s.year) { expected.clone() }
+            else { format!("{:?} {}", s.phase, s.year) }
+        },
+        "" => format_unit(s.get_unit(&province).as_ref()),
+        "order" => String::from(match s.order_result(&province) {
+            Some(true) => "succeeds",
+            Some(false) => "fails",
+            None => "void"
+        }),
+        "dislodged" => format_unit(s.dislodged.iter()
+            .find(|(p, _)| *p == province).map(|(_, u)| u)),
+        "retreats" => {
+            let mut options: Vec<String> = s.retreat_options(&province).iter()
+                .map(|p| format!("{:?}", p)).collect();
+            options.sort();
+            if options.is_empty() { String::from("none") } else { options.join(" ") }
+        },
+        "owner" => s.get_region(&province).and_then(|r| r.owner.as_ref())
+            .map_or(String::from("none"), |p| format!("{:?}", p)),
+        "builds" | "disbands" => {
+            let power = Power::from(subject);
+            let delta = *s.sc_counts().get(&power).unwrap_or(&0) as i32 -
+                *s.unit_counts().get(&power).unwrap_or(&0) as i32;
+            (if keyword == "builds" { delta } else { -delta }).max(0).to_string()
+        },
+        _ => panic!("bad assertion in test file: {}", assertion)
+    };
+
+    // retreat options can be listed in any order
+    let expected = if keyword == "retreats" {
+        let mut options: Vec<&str> = expected.split_whitespace().collect();
+        options.sort();
+        options.join(" ")
+    } else { expected };
+
+    if found == expected { Ok(()) } else {
+        Err(Failure::Assertion(format!("for \"{}\", expected {}, found {}",
+            assertion, expected, found)))
+    }
+}
+
+// run a single test case, returning the first expectation that didn't hold
+fn run_case(lines: &[String]) -> Result<(), Failure> {
+    let mut s = Stpsyr::new(Variant::load("data/standard.variant").unwrap());
     let mut power = None;
-    for line in file.lines() {
-        let line = line.unwrap();
+    let mut set_up = false;
+    for line in lines {
         match line.chars().next() {
-            Some('/') => {},
-            Some('#') => {
-                title = line.chars().skip(2).collect();
-                println!("begin test for test case \"{}\"", title);
-                s = Stpsyr::new("data/standard.csv");
+            Some('@') => {
+                // the first setup directive in a test case clears the board
+                if !set_up {
+                    s.scenario().clear();
+                    set_up = true;
+                }
+                setup(&mut s, line[1..].trim());
+            },
+            Some('=') => {
+                // a phase marker: apply any pending orders, then skip ahead
+                //   (with no orders) until we get to the given phase
+                if power.take().is_some() { s.apply(); }
+                let target = parse_phase(line[1..].trim());
+                for _ in 0..5 {
+                    if (s.phase, s.year) == target { break; }
+                    s.apply();
+                }
+                if (s.phase, s.year) != target {
+                    return Err(Failure::Setup(format!("never got to {:?} {}",
+                        target.0, target.1)));
+                }
             },
             None => {
                 if power.is_some() {
@@ -46,27 +198,74 @@ fn test_from_file(filename: &str) {
             },
             Some(ch) => {
                 if line.contains(':') {
-                    let mut parts = line.split(": ");
-                    let province = parts.next().expect(err_msg);
-                    let real_unit = s.get_unit(&Province::from(province))
-                        .map_or(String::from("empty"), |u| format!("{:?}", u));
-                    let assert_unit = parts.next().expect(err_msg);
-                    if parts.next().is_some() { panic!(err_msg); }
-
-                    if real_unit != assert_unit {
-                        panic!("file {}, test \"{}\": in {}, expected {}, found {}",
-                            filename, title, province, assert_unit, real_unit);
-                    }
+                    check(&s, line)?;
                 } else if ch == ' ' {
-                    s.parse(power.as_ref().unwrap(), line);
+                    s.parse(power.as_ref().unwrap(), line.clone());
                 } else {
-                    power = Some(Power::from(line));
+                    power = Some(Power::from(line.clone()));
                 }
             }
         }
     }
+    Ok(())
+}
+
+// run every test case in a file
+// a case is a "# title" line followed by orders and assertions, and may be
+//   marked as a known failure with a "! reason" line; known failures have to
+//   keep failing (so that the mark gets removed once they're fixed), and have
+//   to fail by getting one of their assertions wrong, not by panicking or
+//   never getting to the right phase
+fn test_from_file(filename: &str) {
+    let f = File::open(filename).expect("error opening test cases");
+    let mut cases: Vec<(String, Option<String>, Vec<String>)> = vec![];
+    for line in BufReader::new(&f).lines() {
+        let line = line.unwrap();
+        match line.chars().next() {
+            Some('/') => {},
+            Some('#') => cases.push((line[1..].trim().to_string(), None, vec![])),
+            Some('!') => {
+                let case = cases.last_mut().expect("known failure outside test case");
+                let reason = case.1.take().map_or(String::new(), |r| r + " ");
+                case.1 = Some(reason + line[1..].trim());
+            },
+            _ => if let Some(case) = cases.last_mut() { case.2.push(line); }
+        }
+    }
+
+    let (mut passed, mut known_failures, mut errors) = (0, 0, vec![]);
+    for (title, known_failure, lines) in &cases {
+        println!("begin test for test case \"{}\"", title);
+        let result = panic::catch_unwind(|| run_case(lines))
+            .unwrap_or(Err(Failure::Panic));
+        match (result, known_failure) {
+            (Ok(()), &None) => passed += 1,
+            (Err(Failure::Assertion(e)), &Some(_)) => {
+                println!("    fails as expected: {}", e);
+                known_failures += 1;
+            },
+            (Err(e), &Some(_)) => errors.push(format!(
+                "test \"{}\" is marked as a known failure, but {}", title, e)),
+            (Ok(()), &Some(_)) => errors.push(format!(
+                "test \"{}\" is marked as a known failure, but passes", title)),
+            (Err(e), &None) => errors.push(format!("test \"{}\": {}", title, e))
+        }
+    }
+
+    println!("{}: {} cases, {} passed, {} known failures", filename,
+        cases.len(), passed, known_failures);
+    for (title, known_failure, _) in &cases {
+        if let Some(ref reason) = *known_failure {
+            println!("    known failure \"{}\": {}", title, reason);
+        }
+    }
+    if !errors.is_empty() {
+        panic!("file {}:\n{}", filename, errors.join("\n"));
+    }
 }
 
+#[test]
+fn test_datc_4d() { test_from_file("tests/datc-4.d.txt"); }
 #[test]
 fn test_datc_6a() { test_from_file("tests/datc-6.a.txt"); }
 #[test]
@@ -79,3 +278,1049 @@ fn test_datc_6d() { test_from_file("tests/datc-6.d.txt"); }
 fn test_datc_6e() { test_from_file("tests/datc-6.e.txt"); }
 #[test]
 fn test_datc_6f() { test_from_file("tests/datc-6.f.txt"); }
+#[test]
+fn test_datc_6g() { test_from_file("tests/datc-6.g.txt"); }
+#[test]
+fn test_datc_6h() { test_from_file("tests/datc-6.h.txt"); }
+#[test]
+fn test_datc_6i() { test_from_file("tests/datc-6.i.txt"); }
+#[test]
+fn test_datc_6j() { test_from_file("tests/datc-6.j.txt"); }
+
+#[test]
+fn test_power_status() {
+    let mut s = Stpsyr::new(Variant::load("data/standard.variant").unwrap());
+    assert_eq!(s.powers.len(), 7);
+    assert_eq!(s.standings()[0].power, Power::from("Russia"));
+    assert_eq!(s.standings()[0].centers, 4);
+
+    // a power in civil disorder has its orders refused
+    s.set_civil_disorder(&Power::from("Italy"), true);
+    assert_eq!(s.power_status(&Power::from("italy")),
+        Some(PowerStatus::CivilDisorder));
+    s.parse(&Power::from("Italy"), "A ven-tyr".to_string());
+    s.apply();
+    assert!(s.get_unit(&Province::from("tyr")).is_none());
+}
+
+#[test]
+fn test_elimination() {
+    let mut s = Stpsyr::new(Variant::load("data/standard.variant").unwrap());
+    let (italy, austria) = (Power::from("Italy"), Power::from("Austria"));
+
+    // a power without units or centers is eliminated at the end of the phase
+    let mut scenario = s.scenario();
+    for p in &["ven", "rom", "nap"] {
+        scenario.remove_unit(&Province::from(*p)).owner(&Province::from(*p), None);
+    }
+    assert!(s.add_order(austria.clone(), Province::from("vie"), Action::Hold));
+    s.apply();
+    assert_eq!(s.power_status(&italy), Some(PowerStatus::Eliminated { year: 1901 }));
+    assert!(!s.survivors().contains(&italy));
+
+    // after which its orders are refused, even for a unit it somehow has
+    s.scenario().unit(&Location::from("ven"), &italy, UnitType::Army);
+    assert!(!s.add_order(italy.clone(), Province::from("ven"), Action::Hold));
+
+    // and it can't be put into (or taken out of) civil disorder
+    s.set_civil_disorder(&italy, false);
+    assert_eq!(s.power_status(&italy), Some(PowerStatus::Eliminated { year: 1901 }));
+
+    // while a power in civil disorder is only refused until it's taken out
+    s.set_civil_disorder(&austria, true);
+    assert!(!s.add_order(austria.clone(), Province::from("vie"), Action::Hold));
+    s.set_civil_disorder(&austria, false);
+    assert!(s.add_order(austria, Province::from("vie"), Action::Hold));
+}
+
+#[test]
+fn test_draw_vote() {
+    let mut s = Stpsyr::new(Variant::load("data/standard.variant").unwrap());
+    let powers = ["Austria", "England", "France", "Germany", "Italy", "Russia",
+        "Turkey"];
+
+    // votes only last for the phase they were cast in
+    let id = s.propose(&Power::from("France"), ProposalKind::DrawAll).unwrap();
+    for p in &powers[..6] { s.vote(&Power::from(*p), id, true); }
+    s.apply();
+    assert!(s.proposals.is_empty());
+
+    // a veto blocks a proposal until it's taken back, and leaves the ids of
+    //   later ones alone
+    let vetoed = s.propose(&Power::from("Austria"), ProposalKind::DrawAll).unwrap();
+    let concession = s.propose(&Power::from("Austria"), ProposalKind::Concession {
+        to: Power::from("Austria")
+    }).unwrap();
+    s.vote(&Power::from("England"), vetoed, false);
+    assert!(s.proposals[vetoed].rejected());
+    s.vote(&Power::from("England"), vetoed, true);
+    assert!(!s.proposals[vetoed].rejected());
+    s.vote(&Power::from("England"), vetoed, false);
+    assert!(s.proposals[vetoed].rejected());
+    s.vote(&Power::from("England"), concession, true);
+    assert_eq!(s.proposals[concession].kind, ProposalKind::Concession {
+        to: Power::from("Austria")
+    });
+    assert_eq!(s.proposals[concession].votes.len(), 2);
+    assert!(!s.proposals[concession].rejected());
+
+    // a power named twice in a draw is only in it once
+    let draw = s.propose(&Power::from("Italy"), ProposalKind::Draw {
+        powers: vec![Power::from("Italy"), Power::from("france"), Power::from("ITALY")]
+    }).unwrap();
+    assert_eq!(s.proposals[draw].kind, ProposalKind::Draw {
+        powers: vec![Power::from("Italy"), Power::from("France")]
+    });
+
+    // and survive serialization while the phase lasts
+    let id = s.propose(&Power::from("France"), ProposalKind::DrawAll).unwrap();
+    for p in &powers[..6] { s.vote(&Power::from(*p), id, true); }
+    let mut s = Stpsyr::deserialize(&s.serialize());
+    assert_eq!(s.proposals[id].votes.len(), 6);
+
+    s.vote(&Power::from("Turkey"), id, true);
+    assert_eq!(s.phase, Phase::GameOver);
+    match s.result {
+        Some(GameResult::Draw { ref powers, year }) =>
+            assert!(powers.len() == 7 && year == 1901),
+        _ => panic!("expected a draw")
+    }
+}
+
+#[test]
+fn test_variant_end() {
+    let variant = Variant::load("data/standard.variant").unwrap();
+    assert_eq!(variant.name, "Standard");
+    assert_eq!(variant.power_info(&Power::from("Turkey")).unwrap().adjective,
+        "Turkish");
+
+    // a power reaching the victory count in the fall wins outright
+    let mut s = Stpsyr::new(variant.clone());
+    s.variant.victory = 4;
+    s.scenario().phase(Phase::FallDiplomacy, 1901);
+    s.apply();
+    assert_eq!(s.phase, Phase::GameOver);
+    assert_eq!(s.result, Some(GameResult::Solo {
+        power: Power::from("Russia"), year: 1901 }));
+
+    // with the most SCs, if more than one power has enough...
+    let mut s = Stpsyr::new(variant.clone());
+    s.variant.victory = 3;
+    s.scenario().phase(Phase::FallDiplomacy, 1901);
+    s.apply();
+    assert_eq!(s.result, Some(GameResult::Solo {
+        power: Power::from("Russia"), year: 1901 }));
+
+    // ... and not at all if they're tied for the most
+    let mut s = Stpsyr::new(variant.clone());
+    s.variant.victory = 3;
+    s.scenario().phase(Phase::FallDiplomacy, 1901)
+        .remove_unit(&Province::from("stp")).owner(&Province::from("stp"), None);
+    s.apply();
+    assert_eq!(s.result, None);
+
+    // but not until the fall retreats are over
+    let mut s = Stpsyr::new(variant.clone());
+    s.variant.victory = 4;
+    s.scenario().phase(Phase::FallDiplomacy, 1901)
+        .remove_unit(&Province::from("stp")).owner(&Province::from("stp"), None)
+        .unit(&Location::from("boh"), &Power::from("Austria"), UnitType::Army)
+        .unit(&Location::from("tyr"), &Power::from("Austria"), UnitType::Army);
+    s.parse(&Power::from("Austria"),
+        String::from("A boh-mun\nA tyr S A boh-mun"));
+    s.apply();
+    assert_eq!(s.phase, Phase::FallRetreats);
+    assert_eq!(s.result, None);
+    s.apply();
+    assert_eq!(s.result, Some(GameResult::Solo {
+        power: Power::from("Austria"), year: 1901 }));
+
+    // and the game is drawn among the survivors after the end year
+    let mut s = Stpsyr::new(variant);
+    s.variant.rules.end_year = Some(1901);
+    s.apply();
+    s.apply();
+    match s.result {
+        Some(GameResult::Draw { ref powers, year }) =>
+            assert!(powers.len() == 7 && year == 1901),
+        _ => panic!("expected a draw")
+    }
+}
+
+#[test]
+fn test_validate_map() {
+    let variant = Variant::load("data/standard.variant").unwrap();
+    assert_eq!(variant.validate(), vec![]);
+
+    // break a fleet border and an army border, put a fleet inland, and make
+    //   a province that isn't an SC a home
+    let mut map = variant.map.clone();
+    for r in &mut map {
+        match &r.province.name[..] {
+            "nth" => r.fleet_borders.retain(|b| b.to.province.name != "nwy"),
+            "mun" => r.army_borders.push(Province::from("xyz")),
+            "par" => r.unit = Some(Unit {
+                owner: Power::from("France"), unit_type: UnitType::Fleet,
+                coast: None
+            }),
+            "pic" => r.home_power = Some(Power::from("France")),
+            _ => {}
+        }
+    }
+    let problems = validate_map(&map);
+    assert_eq!(problems.len(), 4);
+    assert!(problems.contains(&MapProblem::AsymmetricBorder {
+        from: Location::from("nwy"), to: Location::from("nth"),
+        unit_type: UnitType::Fleet
+    }));
+    assert!(problems.contains(&MapProblem::DanglingBorder {
+        from: Location::from("mun"), to: Location::from("xyz"),
+        unit_type: UnitType::Army
+    }));
+    assert!(problems.contains(&MapProblem::BadStartingUnit {
+        province: Province::from("par"), unit_type: UnitType::Fleet
+    }));
+    assert!(problems.contains(&MapProblem::HomeNotCenter {
+        province: Province::from("pic"), power: Power::from("France")
+    }));
+
+    // in a map file, a power's home is an SC it owns or where one of its units
+    //   starts, so a home that's missing its SC flag is caught too
+    let csv = std::fs::read_to_string("data/standard.csv").unwrap()
+        .replace("\nkie,true,Germany,", "\nkie,false,Germany,");
+    let path = std::env::temp_dir().join("stpsyr-home.csv");
+    std::fs::write(&path, csv).unwrap();
+    let problems = validate_map(&load_map(&path).unwrap());
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(problems, vec![MapProblem::HomeNotCenter {
+        province: Province::from("kie"), power: Power::from("Germany")
+    }]);
+}
+
+#[test]
+fn test_province_names() {
+    let mut s = Stpsyr::new(Variant::load("data/standard.variant").unwrap());
+    assert_eq!(s.find_province("North Atlantic Ocean"), Some(Province::from("nao")));
+    assert_eq!(s.find_province("nat"), Some(Province::from("nao")));
+    assert_eq!(s.location_name(&Location::from("stp/sc")),
+        "St. Petersburg (south coast)");
+
+    s.parse(&Power::from("Russia"), "F St. Petersburg (south coast) - Gulf of Bothnia
+A Moscow - Livonia
+A Warsaw S A Moscow - Livonia".to_string());
+    s.parse(&Power::from("England"), "F Edinburgh - Norwegian Sea".to_string());
+    s.apply();
+    assert!(s.get_unit(&Province::from("bot")).is_some());
+    assert!(s.get_unit(&Province::from("lvn")).is_some());
+    assert!(s.get_unit(&Province::from("nwg")).is_some());
+    assert!(s.describe_results().contains(
+        &String::from("Russia: Moscow - Livonia (succeeds)")));
+}
+
+#[test]
+fn test_impassable() {
+    let mut s = Stpsyr::new(Variant::load("data/standard.variant").unwrap());
+    assert_eq!(s.get_region(&Province::from("kie")).unwrap().kind,
+        ProvinceKind::Coastal);
+    assert!(s.get_region(&Province::from("con")).unwrap().canal);
+
+    // pretend Tyrolia is Switzerland
+    for r in &mut s.map {
+        if r.province.name == "tyr" { r.kind = ProvinceKind::Impassable; }
+    }
+    s.parse(&Power::from("Italy"), "A ven-tyr".to_string());
+    s.parse(&Power::from("Austria"), "A vie-tyr".to_string());
+    s.apply();
+    assert!(s.get_unit(&Province::from("tyr")).is_none());
+    assert!(s.get_unit(&Province::from("ven")).is_some());
+
+    // a sea with army borders is a mistake
+    let mut map = s.map.clone();
+    for r in &mut map {
+        if r.province.name == "bur" { r.kind = ProvinceKind::Sea; }
+    }
+    assert_eq!(validate_map(&map), vec![MapProblem::WrongKind {
+        province: Province::from("bur"), kind: ProvinceKind::Sea
+    }]);
+}
+
+#[test]
+fn test_coastal_convoy() {
+    let mut s = Stpsyr::new(Variant::load("data/standard.variant").unwrap());
+    let (germany, kie, den) = (Power::from("Germany"), Province::from("kie"),
+        Province::from("den"));
+    s.scenario().clear()
+        .unit(&Location::from(kie), &germany, UnitType::Army)
+        .unit(&Location::from(den.clone()), &germany, UnitType::Fleet);
+    let orders = "A kie-swe via convoy
+F den C A kie-swe";
+
+    // classical rules: no convoying from Denmark
+    let mut classical = Stpsyr::deserialize(&s.serialize());
+    classical.parse(&germany, orders.to_string());
+    classical.apply();
+    assert!(classical.get_unit(&Province::from("swe")).is_none());
+
+    // but a variant can allow it
+    for r in &mut s.map {
+        if r.province == den { r.convoy = true; }
+    }
+    s.parse(&germany, orders.to_string());
+    s.apply();
+    assert!(s.get_unit(&Province::from("swe")).is_some());
+}
+
+#[test]
+fn test_build_rules() {
+    let mut s = Stpsyr::new(Variant::load("data/standard.variant").unwrap());
+    let germany = Power::from("Germany");
+    s.scenario().clear().phase(Phase::Builds, 1901)
+        .owner(&Province::from("ber"), Some(&germany))
+        .owner(&Province::from("kie"), Some(&germany))
+        .owner(&Province::from("war"), Some(&germany))
+        .unit(&Location::from("kie"), &germany, UnitType::Fleet);
+    let saved = s.serialize();
+
+    // the standard rule only allows home centers
+    s.parse(&germany, "A ber\nA war".to_string());
+    s.apply();
+    assert!(s.get_unit(&Province::from("ber")).is_some());
+    assert!(s.get_unit(&Province::from("war")).is_none());
+
+    // Build Anywhere allows any owned SC
+    let mut s = Stpsyr::deserialize(&saved);
+    assert!(s.variant.rules.set("builds anywhere"));
+    assert_eq!(s.build_sites(&germany).len(), 2);
+    s.parse(&germany, "A ber\nA war".to_string());
+    s.apply();
+    assert!(s.get_unit(&Province::from("ber")).is_some());
+    assert!(s.get_unit(&Province::from("war")).is_some());
+
+    // and with conquest, SCs held after a fall become home centers
+    let mut s = Stpsyr::new(Variant::load("data/standard.variant").unwrap());
+    s.variant.rules.builds = BuildRule::Conquest;
+    let war = Province::from("war");
+    s.scenario().phase(Phase::FallDiplomacy, 1901)
+        .unit(&Location::from(war.clone()), &germany, UnitType::Army);
+    assert!(!s.is_home(s.get_region(&war).unwrap(), &germany));
+    s.apply();
+    assert!(s.is_home(s.get_region(&war).unwrap(), &germany));
+}
+
+#[test]
+fn test_fog_of_war() {
+    let mut s = Stpsyr::new(Variant::load("data/standard.variant").unwrap());
+    let (england, turkey) = (Power::from("England"), Power::from("Turkey"));
+    s.parse(&england, "F lon-nth".to_string());
+    s.parse(&turkey, "A con-bul".to_string());
+    s.apply();
+
+    // without fog of war, everything is visible
+    assert!(s.view(&england).get_unit(&Province::from("bul")).is_some());
+
+    s.variant.rules.fog_of_war = true;
+    let view = s.view(&england);
+    assert!(view.get_unit(&Province::from("nth")).is_some());
+    assert!(view.get_unit(&Province::from("bul")).is_none());
+    assert!(view.get_unit(&Province::from("edi")).is_some());
+    assert!(view.order_result(&Province::from("lon")).is_some());
+    assert!(view.order_result(&Province::from("con")).is_none());
+
+    // pending orders are only seen by whoever gave them
+    s.parse(&turkey, "A bul-gre".to_string());
+    assert!(s.view(&england).orders.is_empty());
+    assert_eq!(s.view(&turkey).orders.len(), 1);
+
+    // and an army out of sight doesn't give itself away by taking over the
+    //   provinces it goes through
+    let mut s = Stpsyr::new(Variant::load("data/standard.variant").unwrap());
+    let austria = Power::from("Austria");
+    s.variant.rules.fog_of_war = true;
+    s.scenario().unit(&Location::from("ser"), &austria, UnitType::Army);
+    s.parse(&austria, "A ser-alb".to_string());
+    s.apply();
+    s.parse(&austria, "A alb-gre".to_string());
+    s.apply();
+    let alb = Province::from("alb");
+    assert_eq!(s.get_region(&alb).unwrap().owner, Some(austria));
+    assert_eq!(s.view(&england).get_region(&alb).unwrap().owner, None);
+    assert_eq!(s.view(&england).get_region(&Province::from("yor")).unwrap().owner,
+        Some(england));
+}
+
+#[test]
+fn test_neutral_garrison() {
+    let mut s = Stpsyr::new(Variant::load("data/standard.variant").unwrap());
+    let (france, germany, neutral) = (Power::from("France"),
+        Power::from("Germany"), Power::neutral());
+    let bel = Province::from("bel");
+    s.scenario().clear().phase(Phase::FallDiplomacy, 1901)
+        .unit(&Location::from(bel.clone()), &neutral, UnitType::Army)
+        .unit(&Location::from("pic"), &france, UnitType::Army)
+        .unit(&Location::from("bur"), &france, UnitType::Army)
+        .unit(&Location::from("hol"), &germany, UnitType::Army)
+        .owner(&Province::from("par"), Some(&france))
+        .owner(&Province::from("ber"), Some(&germany));
+    assert!(!s.can_order(&neutral));
+    let saved = s.serialize();
+
+    // a neutral holds, and nobody can support it
+    s.parse(&france, "A pic-bel".to_string());
+    s.parse(&germany, "A hol S A bel".to_string());
+    s.apply();
+    assert_eq!(s.order_result(&Province::from("hol")), None);
+    assert_eq!(s.order_result(&Province::from("pic")), Some(false));
+    assert!(s.get_region(&bel).unwrap().owner.is_none());
+
+    // and it's removed without a retreat when dislodged
+    let mut s = Stpsyr::deserialize(&saved);
+    s.parse(&france, "A pic-bel\nA bur S A pic-bel".to_string());
+    s.apply();
+    assert!(s.dislodged.is_empty());
+    assert_eq!(s.get_unit(&bel).unwrap().owner, france);
+    assert_eq!(s.get_region(&bel).unwrap().owner, Some(france));
+
+    // and no variant can have a power that would be mistaken for neutrals
+    let path = std::env::temp_dir().join("stpsyr-neutral.variant");
+    let map = std::env::current_dir().unwrap().join("data/standard.csv");
+    std::fs::write(&path, format!("name: Neutral\nmap: {}\nsvg: x.svg\n\
+        year: 1901\nvictory: 18\npower: (Neutral), Neutral, N, 000000\n",
+        map.display())).unwrap();
+    let error = Variant::load(&path).unwrap_err();
+    assert!(error.to_string().contains("reserved"));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_map_builder() {
+    let (england, france) = (Power::from("England"), Power::from("France"));
+    let map = MapBuilder::new()
+        .province("lon", ProvinceKind::Coastal).sc("lon", Some(&england))
+        .province("wal", ProvinceKind::Coastal)
+        .province("bre", ProvinceKind::Coastal).sc("bre", Some(&france))
+        .province("spa", ProvinceKind::Coastal).sc("spa", None)
+        .province("eng", ProvinceKind::Sea).names("eng", "English Channel", &["ech"])
+        .province("mao", ProvinceKind::Sea)
+        .fleet_border("lon", "eng").fleet_border("wal", "eng")
+        .fleet_border("bre", "eng")
+        .fleet_border("bre", "mao").fleet_border("eng", "mao")
+        .fleet_border("mao", "spa/nc").fleet_border("mao", "spa/sc")
+        .army_border("lon", "wal").army_border("bre", "spa")
+        .unit("lon", &england, UnitType::Fleet)
+        .unit("bre", &france, UnitType::Army)
+        .build().unwrap();
+
+    let mut s = Stpsyr::new(Variant {
+        name: String::from("Tiny"),
+        map,
+        svg: String::new(),
+        powers: vec![
+            PowerInfo { power: england.clone(), adjective: String::from("English"),
+                abbreviation: String::from("E"), color: String::from("efc4e4") },
+            PowerInfo { power: france.clone(), adjective: String::from("French"),
+                abbreviation: String::from("F"), color: String::from("79afc6") }
+        ],
+        start_year: 1901,
+        victory: 2,
+        rules: Rules::default()
+    });
+    s.parse(&england, "F lon - English Channel".to_string());
+    s.parse(&france, "A bre-spa".to_string());
+    s.apply();
+    assert!(s.get_unit(&Province::from("eng")).is_some());
+    assert!(s.get_unit(&Province::from("spa")).is_some());
+
+    // problems are reported when the map is built
+    let problems = MapBuilder::new()
+        .province("eng", ProvinceKind::Sea)
+        .province("eng", ProvinceKind::Sea)
+        .unit("eng", &england, UnitType::Army)
+        .army_border("eng", "xyz")
+        .build().unwrap_err();
+    assert!(problems.contains(&MapProblem::DuplicateProvince {
+        province: Province::from("eng")
+    }));
+    assert!(problems.contains(&MapProblem::DanglingBorder {
+        from: Location::from("eng"), to: Location::from("xyz"),
+        unit_type: UnitType::Army
+    }));
+    assert!(problems.contains(&MapProblem::BadStartingUnit {
+        province: Province::from("eng"), unit_type: UnitType::Army
+    }));
+
+    // and a coastal province needs both army and fleet borders
+    let problems = MapBuilder::new()
+        .province("lon", ProvinceKind::Coastal)
+        .province("eng", ProvinceKind::Sea)
+        .fleet_border("lon", "eng")
+        .build().unwrap_err();
+    assert_eq!(problems, vec![MapProblem::WrongKind {
+        province: Province::from("lon"), kind: ProvinceKind::Coastal
+    }]);
+
+    // unless it's an island, which can't have army borders at all
+    let map = MapBuilder::new()
+        .province("ice", ProvinceKind::Island).sc("ice", Some(&england))
+        .province("nao", ProvinceKind::Sea)
+        .fleet_border("ice", "nao")
+        .unit("ice", &england, UnitType::Army)
+        .build();
+    assert!(map.is_ok());
+    let problems = MapBuilder::new()
+        .province("ice", ProvinceKind::Island)
+        .province("nwy", ProvinceKind::Coastal)
+        .province("nao", ProvinceKind::Sea)
+        .fleet_border("ice", "nao").fleet_border("nwy", "nao")
+        .army_border("ice", "nwy")
+        .build().unwrap_err();
+    assert_eq!(problems, vec![MapProblem::WrongKind {
+        province: Province::from("ice"), kind: ProvinceKind::Island
+    }]);
+}
+
+#[test]
+fn test_canal_and_impassable() {
+    let (germany, england) = (Power::from("Germany"), Power::from("England"));
+    let map = MapBuilder::new()
+        .province("bal", ProvinceKind::Sea)
+        .province("hel", ProvinceKind::Sea)
+        .province("nth", ProvinceKind::Sea)
+        .province("kie", ProvinceKind::Coastal).sc("kie", Some(&germany))
+        .canal("kie")
+        .province("ber", ProvinceKind::Coastal).sc("ber", Some(&germany))
+        .province("edi", ProvinceKind::Coastal).sc("edi", Some(&england))
+        .province("yor", ProvinceKind::Coastal)
+        .province("hol", ProvinceKind::Coastal).sc("hol", None)
+        .province("mun", ProvinceKind::Land)
+        .province("swi", ProvinceKind::Impassable)
+        .fleet_border("bal", "kie").fleet_border("kie", "hel")
+        .fleet_border("bal", "ber").fleet_border("hel", "hol")
+        .fleet_border("hel", "nth").fleet_border("nth", "edi")
+        .fleet_border("nth", "yor").fleet_border("edi", "yor")
+        .fleet_border("nth", "swi").fleet_border("swi", "hol")
+        .army_border("kie", "ber").army_border("kie", "mun")
+        .army_border("kie", "hol").army_border("ber", "mun")
+        .army_border("mun", "swi").army_border("edi", "yor")
+        .unit("ber", &germany, UnitType::Fleet)
+        .unit("kie", &germany, UnitType::Army)
+        .unit("edi", &england, UnitType::Army)
+        .build().unwrap();
+
+    let mut s = Stpsyr::new(Variant {
+        name: String::from("Canal"),
+        map,
+        svg: String::new(),
+        powers: vec![
+            PowerInfo { power: germany.clone(), adjective: String::from("German"),
+                abbreviation: String::from("G"), color: String::from("a08a75") },
+            PowerInfo { power: england.clone(), adjective: String::from("English"),
+                abbreviation: String::from("E"), color: String::from("efc4e4") }
+        ],
+        start_year: 1901,
+        victory: 3,
+        rules: Rules::default()
+    });
+
+    // a fleet crosses the canal from one sea to another, and an army can't
+    //   go into an impassable province
+    s.parse(&germany, "F ber-bal\nA kie-mun".to_string());
+    s.apply();
+    s.parse(&germany, "F bal-kie\nA mun-swi".to_string());
+    s.apply();
+    assert_eq!(s.order_result(&Province::from("mun")), None);
+    assert!(s.get_unit(&Province::from("mun")).is_some());
+    assert!(s.get_unit(&Province::from("swi")).is_none());
+    s.parse(&germany, "F kie-hel".to_string());
+    s.apply();
+    assert_eq!(s.get_unit(&Province::from("hel")).map(|u| u.unit_type),
+        Some(UnitType::Fleet));
+
+    // and a convoy can't go through one either, even though it has fleet
+    //   borders (to draw the map)
+    s.scenario().unit(&Location::from("nth"), &england, UnitType::Fleet);
+    s.parse(&england, "A edi-hol\nF nth C A edi-hol".to_string());
+    s.apply();
+    assert!(s.get_unit(&Province::from("edi")).is_some());
+    assert!(s.get_unit(&Province::from("hol")).is_none());
+}
+
+#[test]
+fn test_power_registry() {
+    let mut s = Stpsyr::new(Variant::load("data/standard.variant").unwrap());
+    assert_eq!(s.variant.find_power("austrian"), Some(Power::from("Austria")));
+    assert_eq!(s.variant.find_power("T").unwrap().name, "Turkey");
+    assert_eq!(s.variant.find_power("Prussia"), None);
+
+    // differently cased names are the same power everywhere
+    let mut counts = s.sc_counts();
+    *counts.entry(Power::from("russia")).or_insert(0) += 1;
+    assert_eq!(counts.len(), 7);
+    assert_eq!(counts[&Power::from("RUSSIA")], 5);
+
+    // orders from powers that aren't in the game are ignored, and orders
+    //   from the rest are stored under the variant's name for them
+    s.parse(&Power::from("Prussia"), "A ber-sil".to_string());
+    s.parse(&Power::from("italy"), "A ven-tyr".to_string());
+    assert_eq!(s.orders.len(), 1);
+    assert_eq!(s.orders[0].owner.name, "Italy");
+}
+
+#[test]
+fn test_locations() {
+    // coasts are part of a location, but not of a province
+    assert_eq!(Location::from("spa/nc").province, Province::from("spa/sc"));
+    assert!(Location::from("spa/nc") != Location::from("spa/sc"));
+    assert!(Location::from("spa") != Location::from("spa/sc"));
+
+    // a fleet's coast follows it when it moves
+    let mut s = Stpsyr::new(Variant::load("data/standard.variant").unwrap());
+    let france = Power::from("France");
+    s.scenario().clear()
+        .unit(&Location::from("mao"), &france, UnitType::Fleet);
+    s.parse(&france, "F mao-spa/nc".to_string());
+    s.apply();
+    let spa = Province::from("spa");
+    assert_eq!(s.get_unit(&spa).unwrap().coast, Some('n'));
+    assert_eq!(s.get_region(&spa).unwrap().province, spa);
+}
+
+#[test]
+fn test_parse_diagnostics() {
+    let mut s = Stpsyr::new(Variant::load("data/standard.variant").unwrap());
+    let diagnostics = s.parse(&Power::from("Germany"), "A ber S
+
+F Kiel - Baltic Sea
+A mun - par
+A Berlin - Bavaria
+F kie-bal and more".to_string());
+
+    // blank lines are skipped, but still counted
+    assert_eq!(diagnostics.iter().map(|d| d.number).collect::<Vec<_>>(),
+        vec![1, 3, 4, 5, 6]);
+    let errors: Vec<Option<(ParseErrorKind, Range<usize>)>> = diagnostics.iter()
+        .map(|d| d.error.clone().map(|e| (e.kind, e.span))).collect();
+    assert_eq!(errors, vec![
+        Some((ParseErrorKind::Unparseable, 6..7)),
+        None,
+        Some((ParseErrorKind::Rejected, 0..11)),
+        Some((ParseErrorKind::UnknownProvince { suggestions: vec![] }, 11..18)),
+        Some((ParseErrorKind::Unparseable, 10..18))
+    ]);
+
+    // rejected orders still say what they were understood as
+    assert_eq!(diagnostics[1].order, Some(ParsedOrder::Order {
+        province: Province::from("kie"),
+        unit_type: Some(UnitType::Fleet),
+        action: Action::Move { to: Location::from("bal"), convoyed: false }
+    }));
+    assert!(diagnostics[2].order.is_some());
+    assert_eq!(s.orders.len(), 1);
+
+    // a move without a destination or a coast without a name isn't a hold
+    let diagnostics = s.parse(&Power::from("Germany"),
+        "A mun -\nA mun move\nA mun()".to_string());
+    let errors: Vec<Option<(ParseErrorKind, Range<usize>)>> = diagnostics.iter()
+        .map(|d| d.error.clone().map(|e| (e.kind, e.span))).collect();
+    assert_eq!(errors, vec![
+        Some((ParseErrorKind::Unparseable, 6..7)),
+        Some((ParseErrorKind::Unparseable, 6..10)),
+        Some((ParseErrorKind::Unparseable, 2..6))
+    ]);
+    assert_eq!(s.orders.len(), 1);
+}
+
+#[test]
+fn test_province_matching() {
+    let mut s = Stpsyr::new(Variant::load("data/standard.variant").unwrap());
+    let france = Power::from("France");
+    let names = |names: &[(&str, &str)]| names.iter()
+        .map(|&(p, name)| (Province::from(p), name.to_string())).collect::<Vec<_>>();
+
+    // the start of one province's name is enough
+    let diagnostics = s.parse(&france, "A Par - Burg\nF bre - mid".to_string());
+    assert!(diagnostics.iter().all(|d| d.error.is_none()));
+    assert_eq!(diagnostics[0].order, Some(ParsedOrder::Order {
+        province: Province::from("par"),
+        unit_type: Some(UnitType::Army),
+        action: Action::Move { to: Location::from("bur"), convoyed: false }
+    }));
+
+    // but not of more than one
+    let diagnostics = s.parse(&france, "F bre - north".to_string());
+    assert_eq!(diagnostics[0].error, Some(ParseError {
+        kind: ParseErrorKind::AmbiguousProvince { matches: names(&[
+            ("nao", "North Atlantic Ocean"), ("naf", "North Africa"), ("nth", "North Sea")
+        ]) },
+        span: 8..13
+    }));
+
+    // typos suggest the closest names
+    let diagnostics = s.parse(&france, "A par-bru\nA mar - Burgandy".to_string());
+    let error = diagnostics[0].error.clone().unwrap();
+    assert_eq!(error.kind, ParseErrorKind::UnknownProvince {
+        suggestions: names(&[("bre", "Brest"), ("bur", "Burgundy"), ("pru", "Prussia")])
+    });
+    assert_eq!(error.to_string(), "there's no such province; did you mean \
+        Brest (bre), Burgundy (bur) or Prussia (pru)? (columns 6 to 9)");
+    assert_eq!(diagnostics[1].error.clone().unwrap().kind, ParseErrorKind::UnknownProvince {
+        suggestions: names(&[("bur", "Burgundy")])
+    });
+    assert_eq!(s.orders.len(), 2);
+}
+
+#[test]
+fn test_locales() {
+    let mut s = Stpsyr::new(Variant::load("data/standard.variant").unwrap());
+    let (german, french) = (Locale::builtin("german").unwrap(), Locale::builtin("French").unwrap());
+    assert_eq!(german.keyword("unterstützt"), Some(Keyword::Support));
+    assert_eq!(Keyword::Support.word(), "support");
+
+    // keywords and province names in the locale are understood...
+    let diagnostics = s.parse_localized(&Power::from("Germany"), "F Kiel nach Ostsee
+A Mün unterstützt A Ber - Kie
+Armee Berlin zieht nach Kiel".to_string(), &german);
+    assert!(diagnostics.iter().all(|d| d.error.is_none()));
+    assert_eq!(diagnostics[1].order, Some(ParsedOrder::Order {
+        province: Province::from("mun"),
+        unit_type: Some(UnitType::Army),
+        action: Action::SupportMove { from: Province::from("ber"), to: Province::from("kie"),
+            supported: Some(UnitType::Army) }
+    }));
+
+    // ... as well as English ones
+    let diagnostics = s.parse_localized(&Power::from("France"), "A Par soutient A Mar - Bourgogne
+F Bre holds
+A Mar - Bur".to_string(), &french);
+    assert!(diagnostics.iter().all(|d| d.error.is_none()));
+    assert_eq!(diagnostics[0].order, Some(ParsedOrder::Order {
+        province: Province::from("par"),
+        unit_type: Some(UnitType::Army),
+        action: Action::SupportMove { from: Province::from("mar"), to: Province::from("bur"),
+            supported: Some(UnitType::Army) }
+    }));
+
+    // but not by default
+    let diagnostics = s.parse(&Power::from("Germany"), "A Mun unterstützt A Ber".to_string());
+    assert!(diagnostics[0].error.is_some());
+
+    // and locales can be read from files like the ones that come with stpsyr
+    assert_eq!(Locale::load("data/locales/german.locale").unwrap(), german);
+    assert!(Locale::read("name: Pirate\nparley: arr").is_err());
+}
+
+#[test]
+fn test_retreat_and_build_grammar() {
+    let mut s = Stpsyr::new(Variant::load("data/standard.variant").unwrap());
+    let germany = Power::from("Germany");
+    let orders = |s: &mut Stpsyr, orders: &str| s.parse(&germany, orders.to_string())
+        .into_iter().map(|d| match d.error {
+            Some(error) => Err(error),
+            None => Ok(d.order.unwrap())
+        }).collect::<Vec<_>>();
+
+    // retreats can be written with or without their keywords...
+    s.scenario().clear().phase(Phase::SpringRetreats, 1901)
+        .dislodged(&Location::from("mun"), &germany, UnitType::Army);
+    let to_boh = Ok(ParsedOrder::Retreat {
+        province: Province::from("mun"),
+        unit_type: Some(UnitType::Army),
+        action: RetreatAction::Move { to: Location::from("boh") }
+    });
+    let disband = Ok(ParsedOrder::Retreat {
+        province: Province::from("mun"),
+        unit_type: Some(UnitType::Army),
+        action: RetreatAction::Disband
+    });
+    assert_eq!(orders(&mut s, "A mun - boh\nA mun R boh\nArmy Munich retreats to Bohemia"),
+        vec![to_boh.clone(), to_boh.clone(), to_boh]);
+    assert_eq!(orders(&mut s, "Disband A mun\nA mun disbands\nA mun D"),
+        vec![disband.clone(), disband.clone(), disband]);
+
+    // ... as long as they don't contradict each other
+    assert_eq!(orders(&mut s, "A mun disband boh"), vec![Err(ParseError {
+        kind: ParseErrorKind::Unparseable, span: 6..13
+    })]);
+    // and a retreat needs somewhere to go
+    assert_eq!(orders(&mut s, "A mun -\nA mun R"), vec![
+        Err(ParseError { kind: ParseErrorKind::Unparseable, span: 6..7 }),
+        Err(ParseError { kind: ParseErrorKind::Unparseable, span: 6..7 })
+    ]);
+
+    // and so can builds, removals and waives
+    s.scenario().clear().phase(Phase::Builds, 1901)
+        .owner(&Province::from("ber"), Some(&germany))
+        .owner(&Province::from("kie"), Some(&germany))
+        .owner(&Province::from("mun"), Some(&germany))
+        .unit(&Location::from("mun"), &germany, UnitType::Army);
+    assert_eq!(orders(&mut s, "Build F Kie\nA ber build\nWaive"), vec![
+        Ok(ParsedOrder::Adjust {
+            province: Province::from("kie"),
+            unit_type: Some(UnitType::Fleet),
+            action: AdjustAction::Build { unit_type: UnitType::Fleet, coast: None }
+        }),
+        Ok(ParsedOrder::Adjust {
+            province: Province::from("ber"),
+            unit_type: Some(UnitType::Army),
+            action: AdjustAction::Build { unit_type: UnitType::Army, coast: None }
+        }),
+        Ok(ParsedOrder::Waive)
+    ]);
+    let remove = Ok(ParsedOrder::Adjust {
+        province: Province::from("mun"),
+        unit_type: Some(UnitType::Army),
+        action: AdjustAction::Disband
+    });
+    s.scenario().clear().phase(Phase::Builds, 1901)
+        .owner(&Province::from("ber"), Some(&germany))
+        .unit(&Location::from("ber"), &germany, UnitType::Army)
+        .unit(&Location::from("mun"), &germany, UnitType::Army);
+    for order in &["Remove A Mun", "A Mun disband", "D A mun"] {
+        s.adjusts.clear();
+        assert_eq!(orders(&mut s, order), vec![remove.clone()]);
+    }
+    assert_eq!(orders(&mut s, "Build remove A mun\nWaive A mun")
+        .into_iter().map(|o| o.unwrap_err().span).collect::<Vec<_>>(), vec![6..12, 8..11]);
+
+    // the keywords each line was understood to have are given back
+    let diagnostics = s.parse(&germany, "Remove F Mun".to_string());
+    assert_eq!(diagnostics[0].keywords, vec![(Keyword::Destroy, 0..6), (Keyword::Fleet, 7..8)]);
+    assert_eq!(diagnostics[0].warnings, vec![ParseError {
+        kind: ParseErrorKind::WrongUnitType, span: 7..8
+    }]);
+}
+
+#[test]
+fn test_order_submissions() {
+    let mut s = Stpsyr::new(Variant::load("data/standard.variant").unwrap());
+    let (england, france) = (Power::from("England"), Power::from("France"));
+    let action = |s: &Stpsyr, p: &str| s.orders.iter()
+        .find(|o| o.province == Province::from(p)).map(|o| o.action.clone());
+
+    // a unit's last order replaces the ones before it...
+    s.parse(&england, "F lon-nth\nA lvp-yor".to_string());
+    s.parse(&england, "F lon-eng".to_string());
+    assert_eq!(s.orders.len(), 2);
+    assert_eq!(action(&s, "lon"), Some(Action::Move { to: Location::from("eng"), convoyed: false }));
+
+    // ... and a submission replaces a power's earlier ones entirely
+    s.parse(&france, "A par-bur\nA mar-spa".to_string());
+    s.submit(&england, "F edi-nwg".to_string());
+    assert_eq!(action(&s, "lon"), None);
+    assert_eq!(action(&s, "lvp"), None);
+    assert_eq!(s.orders.len(), 3);
+
+    // units that haven't been ordered hold
+    let orders: Vec<(String, bool)> = s.orders_for(&england).iter()
+        .map(|o| (o.order.to_string(), o.given)).collect();
+    assert_eq!(orders, vec![
+        (String::from("F Edi - Nwg"), true),
+        (String::from("A Lvp H"), false),
+        (String::from("F Lon H"), false)
+    ]);
+
+    // and orders can be withdrawn one at a time
+    assert!(s.withdraw(&france, &Province::from("par")));
+    assert!(!s.withdraw(&france, &Province::from("par")));
+    assert!(!s.withdraw(&france, &Province::from("edi")));
+    assert_eq!(s.orders.iter().map(|o| o.id).collect::<Vec<_>>(), vec![0, 1]);
+    s.apply();
+    assert!(s.get_unit(&Province::from("par")).is_some());
+    assert!(s.get_unit(&Province::from("spa")).is_some());
+    assert!(s.get_unit(&Province::from("nwg")).is_some());
+}
+
+#[test]
+fn test_unit_type_check() {
+    let variant = Variant::load("data/standard.variant").unwrap();
+    let france = Power::from("France");
+    let wrong_type = ParseError { kind: ParseErrorKind::WrongUnitType, span: 0..1 };
+
+    // by default, an order for the wrong type of unit is used with a warning
+    let mut s = Stpsyr::new(variant.clone());
+    let diagnostics = s.parse(&france, "F par-bur\nA mar-spa".to_string());
+    assert_eq!(diagnostics[0].warnings, vec![wrong_type.clone()]);
+    assert!(diagnostics[1].warnings.is_empty());
+    assert_eq!(s.orders.len(), 2);
+
+    // but it can be rejected...
+    let mut s = Stpsyr::new(variant.clone());
+    assert!(s.variant.rules.set("unit-types reject"));
+    let diagnostics = s.parse(&france, "F par-bur".to_string());
+    assert_eq!(diagnostics[0].error, Some(wrong_type));
+    assert_eq!(s.orders.len(), 0);
+
+    // ... or ignored entirely
+    let mut s = Stpsyr::new(variant);
+    assert!(s.variant.rules.set("unit-types ignore"));
+    let diagnostics = s.parse(&france, "Fleet par-bur".to_string());
+    assert!(diagnostics[0].error.is_none() && diagnostics[0].warnings.is_empty());
+    assert_eq!(s.orders.len(), 1);
+}
+
+#[test]
+fn test_order_notation() {
+    let mut s = Stpsyr::new(Variant::load("data/standard.variant").unwrap());
+    let (england, russia) = (Power::from("England"), Power::from("Russia"));
+    s.scenario().clear()
+        .unit(&Location::from("lon"), &england, UnitType::Army)
+        .unit(&Location::from("nth"), &england, UnitType::Fleet)
+        .unit(&Location::from("stp/sc"), &russia, UnitType::Fleet)
+        .unit(&Location::from("mos"), &russia, UnitType::Army)
+        .unit(&Location::from("war"), &russia, UnitType::Army)
+        .unit(&Location::from("ukr"), &russia, UnitType::Army);
+    let saved = s.serialize();
+    s.parse(&england, "A lon-nwy via convoy\nF nth C A lon-nwy".to_string());
+    s.parse(&russia, "F St. Petersburg (south coast) - Gulf of Bothnia
+A mos S A war
+A war H
+A ukr S A war - gal".to_string());
+
+    let displayed: Vec<String> = s.orders.iter().map(|o| o.to_string()).collect();
+    assert_eq!(displayed, vec!["A Lon - Nwy via Convoy", "F Nth C A Lon - Nwy",
+        "F StP/sc - Bot", "A Mos S A War", "A War H", "A Ukr S A War - Gal"]);
+    assert_eq!(format!("{:#}", s.orders[1]), "England: F Nth C A Lon - Nwy");
+
+    // orders can be read back from how they're displayed...
+    for o in &s.orders {
+        let read: Order = format!("{:#}", o).parse().unwrap();
+        assert_eq!(read.owner, o.owner);
+        assert_eq!(read.to_string(), o.to_string());
+    }
+    assert!("Par - Bur".parse::<Order>().is_err());
+    // (supports given without the supported unit's type only get it from the
+    //   board, which FromStr doesn't have)
+    assert_eq!("A Ukr S War - Gal".parse::<Order>().unwrap().to_string(),
+        "A Ukr S War - Gal");
+
+    // ... and given to parse as they are
+    let mut again = Stpsyr::deserialize(&saved);
+    again.parse(&england, displayed[..2].join("\n"));
+    again.parse(&russia, displayed[2..].join("\n"));
+    assert_eq!(again.orders.iter().map(|o| o.to_string()).collect::<Vec<_>>(),
+        displayed);
+
+    // and the same goes for retreats and adjusts
+    for retreat in &["A Mun R Boh", "F Spa/sc D"] {
+        assert_eq!(retreat.parse::<Retreat>().unwrap().to_string(), *retreat);
+    }
+    for adjust in &["Build F StP/nc", "Build A Par", "Destroy Par"] {
+        assert_eq!(adjust.parse::<Adjust>().unwrap().to_string(), *adjust);
+    }
+}
+
+#[test]
+fn test_daide() {
+    let mut s = Stpsyr::new(Variant::load("data/standard.variant").unwrap());
+    let (england, france, russia) = (Power::from("England"), Power::from("France"),
+        Power::from("Russia"));
+    s.scenario().clear()
+        .unit(&Location::from("yor"), &england, UnitType::Army)
+        .unit(&Location::from("nth"), &england, UnitType::Fleet)
+        .unit(&Location::from("lon"), &england, UnitType::Fleet)
+        .unit(&Location::from("par"), &france, UnitType::Army)
+        .unit(&Location::from("mar"), &france, UnitType::Army)
+        .unit(&Location::from("lyo"), &france, UnitType::Fleet)
+        .unit(&Location::from("stp/sc"), &russia, UnitType::Fleet);
+
+    // orders are read with their coasts, and can be written back out
+    let orders = ["( ENG AMY YOR ) CTO NWY VIA ( NTH )",
+        "( ENG FLT NTH ) CVY ( ENG AMY YOR ) CTO NWY",
+        "( ENG FLT LON ) MTO ECH",
+        "( FRA AMY PAR ) SUP ( FRA AMY MAR ) MTO BUR",
+        "( FRA AMY MAR ) MTO BUR",
+        "( FRA FLT GOL ) HLD",
+        "( RUS FLT ( STP SCS ) ) MTO GOB"];
+    for order in &orders { assert_eq!(s.add_daide(order), Ok(())); }
+    let written: Vec<String> = s.orders.iter()
+        .map(|o| s.to_daide(&DaideOrder::Order(o.clone())).unwrap()).collect();
+    assert_eq!(written, orders);
+    match s.parse_daide(orders[2]) {
+        Ok(DaideOrder::Order(o)) =>
+            assert_eq!(o.action, Action::Move { to: Location::from("eng"), convoyed: false }),
+        _ => panic!("couldn't read a DAIDE move")
+    }
+    match s.parse_daide(orders[6]) {
+        Ok(DaideOrder::Order(o)) => {
+            assert_eq!(o.coast, Some('s'));
+            assert_eq!(o.action, Action::Move { to: Location::from("bot"), convoyed: false });
+        },
+        _ => panic!("couldn't read a DAIDE move")
+    }
+
+    // mistakes are pointed out like the ones in parse
+    let kind = |order: &str| s.parse_daide(order).err().map(|e| (e.kind, e.span));
+    assert_eq!(kind("( ENG FLT NTH ) MTO XYZ"),
+        Some((ParseErrorKind::UnknownProvince { suggestions: vec![] }, 20..23)));
+    assert_eq!(kind("( PRU AMY BER ) HLD"), Some((ParseErrorKind::UnknownPower, 2..5)));
+    assert_eq!(kind("( ENG FLT NTH ) MTO"), Some((ParseErrorKind::Unparseable, 16..19)));
+    assert_eq!(kind("( ENG FLT NTH HLD"), Some((ParseErrorKind::Unparseable, 0..1)));
+    assert_eq!(s.add_daide("( FRA FLT PAR ) HLD").unwrap_err().kind,
+        ParseErrorKind::WrongUnitType);
+    assert_eq!(s.add_daide("( ENG FLT LON ) BLD").unwrap_err().kind,
+        ParseErrorKind::Rejected);
+
+    // a convoyed move can't be written until there are fleets to carry it
+    let unconvoyed = s.parse_daide("( FRA AMY MAR ) CTO SPA").unwrap();
+    assert_eq!(s.to_daide(&unconvoyed), None);
+
+    // and builds, removals and waives work in the build phase
+    s.scenario().phase(Phase::Builds, 1901)
+        .owner(&Province::from("stp"), Some(&russia))
+        .owner(&Province::from("mos"), Some(&russia));
+    assert_eq!(s.add_daide("( RUS FLT ( STP NCS ) ) BLD"), Err(ParseError {
+        kind: ParseErrorKind::Rejected, span: 0..27
+    }));
+    assert_eq!(s.add_daide("( RUS AMY MOS ) BLD"), Ok(()));
+    assert_eq!(s.add_daide("( RUS AMY STP ) REM").unwrap_err().kind,
+        ParseErrorKind::WrongUnitType);
+    assert_eq!(s.add_daide("RUS WVE"), Ok(()));
+    assert_eq!(s.to_daide(&DaideOrder::Adjust(s.adjusts[0].clone())).unwrap(),
+        "( RUS AMY MOS ) BLD");
+    assert_eq!(s.to_daide(&DaideOrder::Waive(russia)).unwrap(), "RUS WVE");
+}
+
+#[test]
+fn test_order_document() {
+    let mut s = Stpsyr::new(Variant::load("data/standard.variant").unwrap());
+    let text = "// the first year
+= Spring Diplomacy 1901
+England:
+    F lon-nth
+    A lvp-yor
+french
+    A par-bur
+
+= FallDiplomacy 1901
+E
+    F nth C A yor-nwy
+    A yor-nwy via convoy
+";
+    let document = s.read_document(text).unwrap();
+    assert_eq!(document.turns.len(), 2);
+    assert_eq!(document.turns[0].phase, Some((Phase::SpringDiplomacy, 1901)));
+    assert_eq!(document.turns[0].submissions[1].power, Power::from("France"));
+    assert_eq!(document.turns[1].submissions[0].orders,
+        vec!["F nth C A yor-nwy", "A yor-nwy via convoy"]);
+
+    // documents can be written back out and read again
+    let written = document.to_string();
+    assert!(written.starts_with("= SpringDiplomacy 1901\nEngland\n    F lon-nth\n"));
+    assert_eq!(s.read_document(&written), Ok(document.clone()));
+
+    // and played all at once
+    let diagnostics = s.play_document(&document).unwrap();
+    assert_eq!(diagnostics.len(), 3);
+    assert!(diagnostics.iter().all(|(_, d)| d.iter().all(|d| d.error.is_none())));
+    assert_eq!(s.get_unit(&Province::from("nwy")).unwrap().owner, Power::from("England"));
+    assert_eq!(s.get_unit(&Province::from("bur")).unwrap().owner, Power::from("France"));
+    assert_eq!(s.play_document(&document), Err(DocumentError::WrongPhase {
+        phase: Phase::SpringDiplomacy, year: 1901
+    }));
+
+    assert_eq!(s.read_document("Prussia\n    A ber-sil"),
+        Err(DocumentError::UnknownPower { line: 1, name: String::from("Prussia") }));
+    assert_eq!(s.read_document("// Germany\n    A ber-sil"),
+        Err(DocumentError::MissingPower { line: 2 }));
+    assert_eq!(s.read_document("= Winter 1901"),
+        Err(DocumentError::BadPhase { line: 1 }));
+}